@@ -0,0 +1,29 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use crusp_variables::domains::{FromRangeDomain, OrderedDomain};
+use crusp_variables::int_var::IntVarValues;
+
+// Labeling-like loop that repeatedly tightens the lower bound by one value.
+fn tighten_lower_bound(c: &mut Criterion) {
+    let var: IntVarValues<i32> = IntVarValues::new_from_range(0, 9_999).unwrap();
+    c.bench_function("strict_lowerbound_10000", |b| {
+        b.iter(|| {
+            let mut var = var.clone();
+            for lb in 0..9_999 {
+                var.strict_lowerbound(black_box(&lb)).unwrap();
+            }
+            var
+        })
+    });
+    c.bench_function("remove_min_10000", |b| {
+        b.iter(|| {
+            let mut var = var.clone();
+            for _ in 0..9_999 {
+                var.remove_min().unwrap();
+            }
+            var
+        })
+    });
+}
+
+criterion_group!(benches, tighten_lower_bound);
+criterion_main!(benches);
@@ -0,0 +1,228 @@
+use crate::domains::{FiniteDomain, IterableDomain, OrderedDomain};
+use crate::int_var::IntVarValues;
+use crate::{ArrayOfVariables, Variable, VariableState};
+use std::collections::BTreeMap;
+
+/// Maximal number of assignments that `enumerate_assignments` accepts to enumerate.
+pub const MAX_ASSIGNMENTS: usize = 1 << 20;
+
+/// Returns the minimal and the maximal achievable sums of an array of variables, that is the
+/// sum of the minimal values and the sum of the maximal values, or `None` if the array is
+/// empty (or if one of its variables has an empty domain).
+///
+/// # Parameters
+/// * `arr` - The array of variables.
+pub fn sum_bounds<T, VState, Var>(arr: &impl ArrayOfVariables<T, Var>) -> Option<(T, T)>
+where
+    T: Copy + Ord + Eq + std::ops::Add<Output = T> + Default,
+    VState: VariableState,
+    Var: OrderedDomain<T, VState>,
+{
+    if arr.is_empty() {
+        return None;
+    }
+    let mut bounds = (T::default(), T::default());
+    for var in arr.iter() {
+        bounds = (bounds.0 + *var.min()?, bounds.1 + *var.max()?);
+    }
+    Some(bounds)
+}
+
+/// Returns an iterator over all the full assignments of an array of variables, that is the
+/// cartesian product of their domains, in lexicographic order. An empty array has exactly one
+/// (empty) assignment.
+/// This brute force enumeration is meant for tiny subproblems only.
+///
+/// # Panics
+/// Panics if the number of assignments exceeds `MAX_ASSIGNMENTS`.
+///
+/// # Parameters
+/// * `arr` - The array of variables.
+pub fn enumerate_assignments<T>(
+    arr: &impl ArrayOfVariables<T, IntVarValues<T>>,
+) -> impl Iterator<Item = Vec<T>>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    let count = arr
+        .iter()
+        .try_fold(1usize, |count, var| count.checked_mul(var.size()));
+    assert!(
+        count.is_some_and(|count| count <= MAX_ASSIGNMENTS),
+        "too many assignments to enumerate"
+    );
+    let domains: Vec<Vec<T>> = arr
+        .iter()
+        .map(|var| var.iter().copied().collect())
+        .collect();
+    let mut indices = vec![0usize; domains.len()];
+    let mut done = count == Some(0);
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let assignment = indices
+            .iter()
+            .zip(domains.iter())
+            .map(|(&index, domain)| domain[index])
+            .collect();
+        done = true;
+        for (index, domain) in indices.iter_mut().zip(domains.iter()).rev() {
+            *index += 1;
+            if *index < domain.len() {
+                done = false;
+                break;
+            }
+            *index = 0;
+        }
+        Some(assignment)
+    })
+}
+
+/// Returns, for each value of the union of the domains, the number of variables whose domain
+/// contains it.
+///
+/// # Parameters
+/// * `arr` - The array of variables.
+pub fn value_histogram<T>(arr: &impl ArrayOfVariables<T, IntVarValues<T>>) -> BTreeMap<T, usize>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    let mut histogram = BTreeMap::new();
+    for var in arr.iter() {
+        for value in var.iter() {
+            *histogram.entry(*value).or_insert(0) += 1;
+        }
+    }
+    histogram
+}
+
+/// Values of a fully assigned array of variables, in the order of the array.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Solution<T> {
+    values: Vec<T>,
+}
+
+impl<T> Solution<T>
+where
+    T: Copy,
+{
+    /// Returns the values of the variables of `arr`, or `None` if a variable is not affected.
+    ///
+    /// # Parameters
+    /// * `arr` - The array of variables.
+    pub fn from_array<Var>(arr: &impl ArrayOfVariables<T, Var>) -> Option<Solution<T>>
+    where
+        Var: Variable<T>,
+    {
+        let values = arr
+            .iter()
+            .map(|var| var.value().copied())
+            .collect::<Option<Vec<_>>>()?;
+        Some(Solution { values })
+    }
+
+    /// Returns the value of the variable at that position.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    ///
+    /// # Parameters
+    /// * `i` - The position of the variable.
+    pub fn index(&self, i: usize) -> T {
+        self.values[i]
+    }
+
+    /// Returns the number of variables.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` iff the solution has no variable.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the values of the variables.
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{AssignableDomain, FromValuesDomain};
+    use crate::int_var::IntVarValues;
+    use crate::ArrayOfVars;
+
+    #[test]
+    fn test_sum_bounds() {
+        let vars = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(1..=4).unwrap(),
+            IntVarValues::new_from_values(vec![-2, 5]).unwrap(),
+            IntVarValues::new_from_values(vec![3]).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(sum_bounds(&vars), Some((2, 12)));
+        let vars = ArrayOfVars::<i32, IntVarValues<i32>>::new_from_iter(vec![]).unwrap();
+        assert_eq!(sum_bounds(&vars), None);
+    }
+
+    #[test]
+    fn test_enumerate_assignments() {
+        let vars = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![5, 7]).unwrap(),
+        ])
+        .unwrap();
+        let assignments: Vec<_> = enumerate_assignments(&vars).collect();
+        assert_eq!(
+            assignments,
+            vec![vec![1, 5], vec![1, 7], vec![2, 5], vec![2, 7]]
+        );
+        let vars = ArrayOfVars::<i32, IntVarValues<i32>>::new_from_iter(vec![]).unwrap();
+        assert_eq!(enumerate_assignments(&vars).count(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_enumerate_assignments_too_large() {
+        let vars = ArrayOfVars::new(3, IntVarValues::new_from_values(0..1000).unwrap()).unwrap();
+        let _ = enumerate_assignments(&vars);
+    }
+
+    #[test]
+    fn test_value_histogram() {
+        let vars = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(1..=3).unwrap(),
+            IntVarValues::new_from_values(vec![2, 3]).unwrap(),
+            IntVarValues::new_from_values(vec![3, 7]).unwrap(),
+        ])
+        .unwrap();
+        let histogram = value_histogram(&vars);
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            vec![(1, 1), (2, 2), (3, 3), (7, 1)]
+        );
+    }
+
+    #[test]
+    fn test_solution() {
+        let mut vars = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(vec![4]).unwrap(),
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![-3]).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(Solution::from_array(&vars), None);
+        vars.get_mut(1).unwrap().set_value(2).unwrap();
+        let solution = Solution::from_array(&vars).unwrap();
+        assert_eq!(solution.len(), 3);
+        assert_eq!(
+            (solution.index(0), solution.index(1), solution.index(2)),
+            (4, 2, -3)
+        );
+        assert_eq!(solution.as_slice(), &[4, 2, -3]);
+    }
+}
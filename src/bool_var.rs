@@ -26,7 +26,7 @@ impl BoolVar {
 }
 
 impl IterableDomain<bool> for BoolVar {
-    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &bool> + 'a> {
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a bool> + 'a> {
         unimplemented!()
     }
 }
@@ -2,6 +2,9 @@
 use super::VariableObserver;
 use super::{Variable, VariableError, VariableState};
 #[cfg(feature = "observer")]
+use crusp_core::VariableId;
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "observer")]
 use std::marker::PhantomData;
 
 #[cfg(feature = "observer")]
@@ -25,6 +28,33 @@ where
     }
 }
 
+// Forwards the events unchanged.
+#[cfg(feature = "observer")]
+impl<VState> VariableObserver<VState> for NoOpObserver<VState>
+where
+    VState: VariableState,
+{
+    fn push(
+        &mut self,
+        _vid: VariableId,
+        event: Result<VState, VariableError>,
+    ) -> Result<VState, VariableError> {
+        event
+    }
+
+    fn push_change(&mut self, _vid: VariableId, event: VState) -> Result<VState, VariableError> {
+        Ok(event)
+    }
+
+    fn push_error(
+        &mut self,
+        _vid: VariableId,
+        event: VariableError,
+    ) -> Result<VState, VariableError> {
+        Err(event)
+    }
+}
+
 /// Trait that defines variables with finite domains. In other words the number of elements
 /// of the domain is countable). Every variable should have a finite domain.
 pub trait FiniteDomain<Type>: Variable<Type> {
@@ -35,7 +65,24 @@ pub trait FiniteDomain<Type>: Variable<Type> {
 /// Trait that definies variable allowing to iter through the elements of its domain.
 pub trait IterableDomain<Type>: FiniteDomain<Type> {
     /// Returns an `Iterator` over the elements of the domain.
-    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &Type> + 'a>;
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Type> + 'a>;
+}
+
+/// Hashes the values of a domain as a sorted sequence, so that two variables with the same
+/// domain have the same hash whatever their representation.
+///
+/// # Parameters
+/// * `domain` - The variable to hash.
+/// * `state` - The hasher.
+pub fn domain_hash<Type, Domain, H>(domain: &Domain, state: &mut H)
+where
+    Type: Hash + Ord,
+    Domain: IterableDomain<Type>,
+    H: Hasher,
+{
+    let mut values: Vec<_> = domain.iter().collect();
+    values.sort();
+    values.hash(state);
 }
 
 /// Trait that defines variable that can be assigned to a specific value.
@@ -99,12 +146,98 @@ where
         );
         self.max().expect(&error)
     }
+    /// Removes the values greater or equal to `ub`.
+    /// Returns an error of type `VariableError::BoundWipeout { lower: false }` if no value
+    /// is left, otherwise returns the correct `VariableState`.
     fn strict_upperbound(&mut self, ub: &Type) -> Result<VState, VariableError>;
+    /// Removes the values strictly greater than `ub`.
+    /// Returns an error of type `VariableError::BoundWipeout { lower: false }` if no value
+    /// is left, otherwise returns the correct `VariableState`.
     fn weak_upperbound(&mut self, ub: &Type) -> Result<VState, VariableError>;
+    /// Removes the values less or equal to `lb`.
+    /// Returns an error of type `VariableError::BoundWipeout { lower: true }` if no value
+    /// is left, otherwise returns the correct `VariableState`.
     fn strict_lowerbound(&mut self, lb: &Type) -> Result<VState, VariableError>;
+    /// Removes the values strictly less than `lb`.
+    /// Returns an error of type `VariableError::BoundWipeout { lower: true }` if no value
+    /// is left, otherwise returns the correct `VariableState`.
     fn weak_lowerbound(&mut self, lb: &Type) -> Result<VState, VariableError>;
 }
 
+/// Adaptor that reverses the order of an ordered variable: `min` and `max` are swapped, and
+/// so are the upper bound and lower bound methods (e.g. `strict_upperbound` on the adaptor
+/// behaves like `strict_lowerbound` on the underlying variable).
+/// The states returned by the bound methods are the ones of the underlying variable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reversed<Var> {
+    var: Var,
+}
+
+impl<Var> Reversed<Var> {
+    /// Creates a new reversed view over `var`.
+    pub fn new(var: Var) -> Self {
+        Reversed { var }
+    }
+
+    /// Returns a reference to the underlying variable.
+    pub fn inner(&self) -> &Var {
+        &self.var
+    }
+
+    /// Returns the underlying variable.
+    pub fn into_inner(self) -> Var {
+        self.var
+    }
+}
+
+impl<Type, Var> Variable<Type> for Reversed<Var>
+where
+    Var: Variable<Type>,
+{
+    fn is_affected(&self) -> bool {
+        self.var.is_affected()
+    }
+
+    fn value(&self) -> Option<&Type> {
+        self.var.value()
+    }
+}
+
+impl<Type, Var> FiniteDomain<Type> for Reversed<Var>
+where
+    Var: FiniteDomain<Type>,
+{
+    fn size(&self) -> usize {
+        self.var.size()
+    }
+}
+
+impl<Type, VState, Var> OrderedDomain<Type, VState> for Reversed<Var>
+where
+    VState: VariableState,
+    Type: Ord + Eq,
+    Var: OrderedDomain<Type, VState>,
+{
+    fn min(&self) -> Option<&Type> {
+        self.var.max()
+    }
+    fn max(&self) -> Option<&Type> {
+        self.var.min()
+    }
+    fn strict_upperbound(&mut self, ub: &Type) -> Result<VState, VariableError> {
+        self.var.strict_lowerbound(ub)
+    }
+    fn weak_upperbound(&mut self, ub: &Type) -> Result<VState, VariableError> {
+        self.var.weak_lowerbound(ub)
+    }
+    fn strict_lowerbound(&mut self, lb: &Type) -> Result<VState, VariableError> {
+        self.var.strict_upperbound(lb)
+    }
+    fn weak_lowerbound(&mut self, lb: &Type) -> Result<VState, VariableError> {
+        self.var.weak_upperbound(lb)
+    }
+}
+
 /// Trait that defines variable which the underlying `Type` implements the `Ord`
 /// trait (i.e. the underlying type is totally ordered).
 #[cfg(feature = "observer")]
@@ -131,6 +264,9 @@ where
         );
         self.max().expect(&error)
     }
+    /// Removes the values greater or equal to `ub`.
+    /// Returns an error of type `VariableError::BoundWipeout { lower: false }` if no value
+    /// is left, otherwise returns the correct `VariableState`.
     fn strict_upperbound<Observer>(
         &mut self,
         observer: &mut Observer,
@@ -138,6 +274,9 @@ where
     ) -> Result<VState, VariableError>
     where
         Observer: VariableObserver<VState>;
+    /// Removes the values strictly greater than `ub`.
+    /// Returns an error of type `VariableError::BoundWipeout { lower: false }` if no value
+    /// is left, otherwise returns the correct `VariableState`.
     fn weak_upperbound<Observer>(
         &mut self,
         observer: &mut Observer,
@@ -145,6 +284,9 @@ where
     ) -> Result<VState, VariableError>
     where
         Observer: VariableObserver<VState>;
+    /// Removes the values less or equal to `lb`.
+    /// Returns an error of type `VariableError::BoundWipeout { lower: true }` if no value
+    /// is left, otherwise returns the correct `VariableState`.
     fn strict_lowerbound<Observer>(
         &mut self,
         observer: &mut Observer,
@@ -152,6 +294,9 @@ where
     ) -> Result<VState, VariableError>
     where
         Observer: VariableObserver<VState>;
+    /// Removes the values strictly less than `lb`.
+    /// Returns an error of type `VariableError::BoundWipeout { lower: true }` if no value
+    /// is left, otherwise returns the correct `VariableState`.
     fn weak_lowerbound<Observer>(
         &mut self,
         observer: &mut Observer,
@@ -311,10 +456,7 @@ where
     ///
     /// # Parameters
     /// * `value` - The variable to compare to.
-    fn in_sorted_values<Values: Iterator<Item = Type>>(
-        &mut self,
-        values: Values,
-    ) -> Result<VState, VariableError>
+    fn in_sorted_values<Values>(&mut self, values: Values) -> Result<VState, VariableError>
     where
         Values: IntoIterator<Item = Type>;
 }
@@ -331,7 +473,7 @@ where
     ///
     /// # Parameters
     /// * `value` - The variable to compare to.
-    fn in_sorted_values<Observer, Values: Iterator<Item = Type>>(
+    fn in_sorted_values<Observer, Values>(
         &mut self,
         observer: &mut Observer,
         values: Values,
@@ -374,7 +516,7 @@ where
 {
     /// Forces the domain of `self` to satisfies a precedence relation
     /// with `value`.
-    /// Returns an error of type `VariableError::DomainWipeout` if
+    /// Returns an error of type `VariableError::BoundWipeout` if
     /// the minimal value of `self` is greater or equal to the maximal
     /// value of `value`, otherwise returns the correct `VariableState`.
     ///
@@ -394,7 +536,7 @@ where
     }
     /// Forces the domain of `self` to satisfies a weak precedence relation
     /// with `value`.
-    /// Returns an error of type `VariableError::DomainWipeout` if
+    /// Returns an error of type `VariableError::BoundWipeout` if
     /// the minimal value of `self` is greater to the maximal
     /// value of `value`, otherwise returns the correct `VariableState`.
     ///
@@ -414,7 +556,7 @@ where
     }
     /// Forces the domain of `value` to satisfies a strict precedence relation
     /// with `self`.
-    /// Returns an error of type `VariableError::DomainWipeout` if
+    /// Returns an error of type `VariableError::BoundWipeout` if
     /// the minimal value of `value` is greater or equal to the maximal
     /// value of `self`, otherwise returns the correct `VariableState`.
     ///
@@ -435,7 +577,7 @@ where
 
     /// Forces the domain of `value` to satisfies a weak precedence relation
     /// with `self`.
-    /// Returns an error of type `VariableError::DomainWipeout` if
+    /// Returns an error of type `VariableError::BoundWipeout` if
     /// the minimal value of `value` is greater to the maximal
     /// value of `self`, otherwise returns the correct `VariableState`.
     ///
@@ -455,7 +597,7 @@ where
     }
     /// Forces the domains of two variables two have the same bounds (the does not imply to have
     /// the same domain).
-    /// Returns an error of type `VariableError::DomainWipeout` if
+    /// Returns an error of type `VariableError::BoundWipeout` if
     /// the two variables can't have the same bounds (i.e. no common value),
     /// otherwise returns the correct `VariableState`.
     ///
@@ -499,3 +641,45 @@ where
         Ok((x, y))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{domain_hash, FromValuesDomain, IterableDomain, OrderedDomain};
+    use crate::int_var::{IntVarValues, IntVarValuesBy};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+
+    #[test]
+    fn test_domain_hash() {
+        let hash = |f: &dyn Fn(&mut DefaultHasher)| {
+            let mut state = DefaultHasher::new();
+            f(&mut state);
+            state.finish()
+        };
+        let x = IntVarValues::new_from_values(vec![3, 1, 2]).unwrap();
+        let y = IntVarValues::new_from_values(1..=3).unwrap();
+        let z = IntVarValues::new_from_values(1..=4).unwrap();
+        let x_hash = hash(&|state| domain_hash(&x, state));
+        assert_eq!(x_hash, hash(&|state| domain_hash(&y, state)));
+        assert_eq!(x_hash, hash(&|state| domain_hash(&y.freeze(), state)));
+        assert_ne!(x_hash, hash(&|state| domain_hash(&z, state)));
+
+        let by_key = IntVarValuesBy::new_from_values_by(vec![1, 2, 3], |v: &i32| -v).unwrap();
+        assert_eq!(by_key.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(x_hash, hash(&|state| domain_hash(&by_key, state)));
+    }
+
+    #[test]
+    fn test_reversed() {
+        let var = IntVarValues::new_from_values(1..=5).unwrap();
+        let mut reversed = var.clone().reversed();
+        assert_eq!(reversed.min(), var.max());
+        assert_eq!(reversed.max(), var.min());
+        assert!(reversed.strict_upperbound(&2).is_ok());
+        assert_eq!(reversed.inner().as_slice(), &[3, 4, 5]);
+        assert!(reversed.weak_lowerbound(&4).is_ok());
+        assert_eq!(reversed.inner().as_slice(), &[3, 4]);
+        assert_eq!(reversed.unchecked_min(), &4);
+        assert!(reversed.strict_lowerbound(&3).is_err());
+    }
+}
@@ -13,11 +13,22 @@ use crusp_graph::GraphEvent;
 // pub use self::values::{IntVarValues, IntVarValuesArray, IntVarValuesRefArray};
 // pub use self::values::{IntVarBitset, IntVarBitsetArray, IntVarBitsetRefArray};
 
-pub use self::values::{IntVarValues, IntVarValuesBuilder};
+pub use self::bounds::IntVarBounds;
+pub use self::by_key::IntVarValuesBy;
+pub use self::frozen::FrozenVar;
+pub use self::values::{
+    arc_consistent, minkowski_sum, product_domain, DomainSummary, IntVarValues,
+    IntVarValuesBuilder, PruneConflict, PruneOp,
+};
 
 mod bitset;
 mod bounds;
+mod by_key;
+mod frozen;
 mod intervals;
+#[cfg(feature = "roaring")]
+mod roaring;
+mod storage;
 mod values;
 
 /// Describes the state of a variable after its domain is updated.
@@ -81,6 +92,42 @@ impl std::ops::BitOr for IntVariableState {
 
 impl VariableState for IntVariableState {}
 
+impl Subsumed for IntVariableState {
+    /// # Subsomption relations
+    /// * `MaxBoundChange` subsumed `BoundsChange`
+    /// * `MinBoundChange` subsumed `BoundsChange`
+    /// * `BoundsChange` subsumed `ValuesChange`
+    /// * `ValuesChange` subsumed `NoChange`
+    fn is_subsumed_under(&self, val: &Self) -> bool {
+        // not correct yet
+        // (make_bitflags!(self) & make_bitflags!(val)).contains(make_bitflags!(self))
+        match *self {
+            IntVariableState::MaxBoundChange => *val == IntVariableState::MaxBoundChange,
+            IntVariableState::MinBoundChange => *val == IntVariableState::MinBoundChange,
+            IntVariableState::BoundsChange => {
+                *val != IntVariableState::ValuesChange && *val != IntVariableState::NoChange
+            }
+            IntVariableState::ValuesChange => *val != IntVariableState::NoChange,
+            IntVariableState::NoChange => true,
+            _ => false,
+        }
+    }
+}
+
+/// Order in which the values of a variable are tried when branching on it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BranchStrategy {
+    /// The values in ascending order.
+    MinFirst,
+    /// The values in descending order.
+    MaxFirst,
+    /// The median value first, then the values closest to it (the lowest first on ties).
+    Median,
+    /// The median value first, then the medians of the lower and upper halves, and so on
+    /// in breadth-first order.
+    Bisect,
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -291,25 +338,3 @@ mod tests {
         );
     }
 }
-
-impl Subsumed for IntVariableState {
-    /// # Subsomption relations
-    /// * `MaxBoundChange` subsumed `BoundsChange`
-    /// * `MinBoundChange` subsumed `BoundsChange`
-    /// * `BoundsChange` subsumed `ValuesChange`
-    /// * `ValuesChange` subsumed `NoChange`
-    fn is_subsumed_under(&self, val: &Self) -> bool {
-        // not correct yet
-        // (make_bitflags!(self) & make_bitflags!(val)).contains(make_bitflags!(self))
-        match *self {
-            IntVariableState::MaxBoundChange => *val == IntVariableState::MaxBoundChange,
-            IntVariableState::MinBoundChange => *val == IntVariableState::MinBoundChange,
-            IntVariableState::BoundsChange => {
-                *val != IntVariableState::ValuesChange && *val != IntVariableState::NoChange
-            }
-            IntVariableState::ValuesChange => *val != IntVariableState::NoChange,
-            IntVariableState::NoChange => true,
-            _ => false,
-        }
-    }
-}
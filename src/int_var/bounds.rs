@@ -1 +1,180 @@
+use super::IntVariableState;
+use crate::domains::{FiniteDomain, FromRangeDomain, OrderedDomain};
+use crate::{Variable, VariableError};
+use num::PrimInt;
+use std::convert::TryFrom;
 
+/// Integer variable whose domain is represented by its bounds `[min;max]`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IntVarBounds<T>
+where
+    T: PrimInt,
+{
+    min: T,
+    max: T,
+}
+
+impl<T> IntVarBounds<T>
+where
+    T: PrimInt,
+{
+    /// Returns a new variable with the domain `[min;max]` or `None` if `min > max`.
+    ///
+    /// # Parameters
+    /// * `min` - The minimal value of the domain.
+    /// * `max` - The maximal value of the domain.
+    pub fn try_new(min: T, max: T) -> Option<IntVarBounds<T>> {
+        if min > max {
+            None
+        } else {
+            Some(IntVarBounds { min, max })
+        }
+    }
+
+    /// Returns the exact number of values of the domain or `None` if it does not fit in a
+    /// `u128` (only possible for 128 bits integer types). Unlike `size`, the result does not
+    /// saturate for 64 bits integer types.
+    pub fn try_size(&self) -> Option<u128> {
+        let min = self.min.to_i128()?;
+        let max = self.max.to_i128()?;
+        (max.checked_sub(min)? as u128).checked_add(1)
+    }
+}
+
+impl<T> Variable<T> for IntVarBounds<T>
+where
+    T: PrimInt,
+{
+    fn is_affected(&self) -> bool {
+        self.min == self.max
+    }
+
+    fn value(&self) -> Option<&T> {
+        if self.min == self.max {
+            Some(&self.min)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> FiniteDomain<T> for IntVarBounds<T>
+where
+    T: PrimInt,
+{
+    /// Returns the number of values of the domain, saturated to `usize::MAX` if it does not
+    /// fit in a `usize` (e.g. `[i64::MIN;i64::MAX]`). Such large domains should never be
+    /// materialized value by value.
+    fn size(&self) -> usize {
+        self.try_size()
+            .and_then(|size| usize::try_from(size).ok())
+            .unwrap_or(usize::MAX)
+    }
+}
+
+impl<T> FromRangeDomain<T> for IntVarBounds<T>
+where
+    T: PrimInt,
+{
+    fn new_from_range(min: T, max: T) -> Option<IntVarBounds<T>> {
+        IntVarBounds::try_new(min, max)
+    }
+}
+
+impl<T> OrderedDomain<T, IntVariableState> for IntVarBounds<T>
+where
+    T: PrimInt,
+{
+    fn min(&self) -> Option<&T> {
+        Some(&self.min)
+    }
+    fn max(&self) -> Option<&T> {
+        Some(&self.max)
+    }
+
+    fn strict_upperbound(&mut self, ub: &T) -> Result<IntVariableState, VariableError> {
+        if self.max < *ub {
+            Ok(IntVariableState::NoChange)
+        } else if self.min >= *ub {
+            Err(VariableError::BoundWipeout { lower: false })
+        } else {
+            self.max = *ub - T::one();
+            Ok(IntVariableState::MaxBoundChange)
+        }
+    }
+
+    fn weak_upperbound(&mut self, ub: &T) -> Result<IntVariableState, VariableError> {
+        if self.max <= *ub {
+            Ok(IntVariableState::NoChange)
+        } else if self.min > *ub {
+            Err(VariableError::BoundWipeout { lower: false })
+        } else {
+            self.max = *ub;
+            Ok(IntVariableState::MaxBoundChange)
+        }
+    }
+
+    fn strict_lowerbound(&mut self, lb: &T) -> Result<IntVariableState, VariableError> {
+        if self.min > *lb {
+            Ok(IntVariableState::NoChange)
+        } else if self.max <= *lb {
+            Err(VariableError::BoundWipeout { lower: true })
+        } else {
+            self.min = *lb + T::one();
+            Ok(IntVariableState::MinBoundChange)
+        }
+    }
+
+    fn weak_lowerbound(&mut self, lb: &T) -> Result<IntVariableState, VariableError> {
+        if self.min >= *lb {
+            Ok(IntVariableState::NoChange)
+        } else if self.max < *lb {
+            Err(VariableError::BoundWipeout { lower: true })
+        } else {
+            self.min = *lb;
+            Ok(IntVariableState::MinBoundChange)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size() {
+        let var = IntVarBounds::try_new(-2, 3).unwrap();
+        assert_eq!(var.size(), 6);
+        assert_eq!(var.try_size(), Some(6));
+        let var = IntVarBounds::try_new(i64::MIN, i64::MAX).unwrap();
+        assert_eq!(var.size(), usize::MAX);
+        assert_eq!(var.try_size(), Some(1u128 << 64));
+        let var = IntVarBounds::try_new(u64::MIN, u64::MAX).unwrap();
+        assert_eq!(var.size(), usize::MAX);
+        assert_eq!(IntVarBounds::try_new(1, 0), None);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut var = IntVarBounds::try_new(0, 10).unwrap();
+        assert_eq!(
+            var.strict_upperbound(&8),
+            Ok(IntVariableState::MaxBoundChange)
+        );
+        assert_eq!(
+            var.weak_lowerbound(&3),
+            Ok(IntVariableState::MinBoundChange)
+        );
+        assert_eq!(var.weak_upperbound(&7), Ok(IntVariableState::NoChange));
+        assert_eq!((var.min(), var.max()), (Some(&3), Some(&7)));
+        assert_eq!(
+            var.strict_lowerbound(&7),
+            Err(VariableError::BoundWipeout { lower: true })
+        );
+        assert_eq!(
+            var.weak_upperbound(&3),
+            Ok(IntVariableState::MaxBoundChange)
+        );
+        assert_eq!(var.value(), Some(&3));
+    }
+}
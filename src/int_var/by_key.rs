@@ -0,0 +1,212 @@
+use super::IntVariableState;
+use crate::domains::{FiniteDomain, IterableDomain, OrderedDomain};
+use crate::{Variable, VariableError};
+
+/// Integer variable whose values are ordered by a key (e.g. `|x|`) instead of the natural
+/// order of `T`. The bounds follow the key order: `min` is the value with the smallest key.
+/// Values with the same key are ordered by `T`, and so are the bounds given to the bound
+/// methods (e.g. with `|x|` as key, `-2` is before `2`).
+#[derive(Clone)]
+pub struct IntVarValuesBy<T, F> {
+    domain: Vec<T>,
+    key: F,
+}
+
+impl<T, K, F> IntVarValuesBy<T, F>
+where
+    T: Copy + Ord,
+    K: Ord,
+    F: Fn(&T) -> K + Clone,
+{
+    /// Returns a new variable from a list of values ordered by `key`, or `None` if the list
+    /// of values is empty.
+    ///
+    /// # Parameters
+    /// * `values` - The values of the domain.
+    /// * `key` - The key that orders the values.
+    pub fn new_from_values_by<Values>(values: Values, key: F) -> Option<IntVarValuesBy<T, F>>
+    where
+        Values: IntoIterator<Item = T>,
+    {
+        let mut domain: Vec<_> = values.into_iter().collect();
+        domain.sort_unstable();
+        domain.dedup();
+        domain.sort_by_key(|v| key(v));
+        if domain.is_empty() {
+            None
+        } else {
+            Some(IntVarValuesBy { domain, key })
+        }
+    }
+
+    /// Returns the domain as a slice sorted by key.
+    pub fn as_slice(&self) -> &[T] {
+        &self.domain
+    }
+
+    // Returns the number of values ordered before `value` (including `value` itself when
+    // `inclusive` is `true`), comparing the pairs `(key, value)` so that ties are ordered by `T`.
+    fn rank(&self, value: &T, inclusive: bool) -> usize {
+        let key = &self.key;
+        let bound = (key(value), *value);
+        self.domain.partition_point(|v| {
+            let v = (key(v), *v);
+            v < bound || (inclusive && v == bound)
+        })
+    }
+
+    // Keeps the values from the position `index` when `lower` is `true`, or the values before
+    // it otherwise.
+    fn cut(&mut self, lower: bool, index: usize) -> Result<IntVariableState, VariableError> {
+        let size = self.domain.len();
+        let kept = if lower { size - index } else { index };
+        if kept == 0 {
+            self.domain.clear();
+            Err(VariableError::BoundWipeout { lower })
+        } else if kept == size {
+            Ok(IntVariableState::NoChange)
+        } else if lower {
+            self.domain.drain(..index);
+            Ok(IntVariableState::MinBoundChange)
+        } else {
+            self.domain.truncate(index);
+            Ok(IntVariableState::MaxBoundChange)
+        }
+    }
+}
+
+impl<T, K, F> Variable<T> for IntVarValuesBy<T, F>
+where
+    T: Copy + Ord,
+    K: Ord,
+    F: Fn(&T) -> K + Clone,
+{
+    fn is_affected(&self) -> bool {
+        self.domain.len() == 1
+    }
+
+    fn value(&self) -> Option<&T> {
+        if self.domain.len() == 1 {
+            self.domain.first()
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, K, F> FiniteDomain<T> for IntVarValuesBy<T, F>
+where
+    T: Copy + Ord,
+    K: Ord,
+    F: Fn(&T) -> K + Clone,
+{
+    fn size(&self) -> usize {
+        self.domain.len()
+    }
+}
+
+impl<T, K, F> IterableDomain<T> for IntVarValuesBy<T, F>
+where
+    T: Copy + Ord,
+    K: Ord,
+    F: Fn(&T) -> K + Clone,
+{
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a T> + 'a> {
+        Box::new(self.domain.iter())
+    }
+}
+
+impl<T, K, F> OrderedDomain<T, IntVariableState> for IntVarValuesBy<T, F>
+where
+    T: Copy + Ord,
+    K: Ord,
+    F: Fn(&T) -> K + Clone,
+{
+    fn min(&self) -> Option<&T> {
+        self.domain.first()
+    }
+    fn max(&self) -> Option<&T> {
+        self.domain.last()
+    }
+
+    fn strict_upperbound(&mut self, ub: &T) -> Result<IntVariableState, VariableError> {
+        let index = self.rank(ub, false);
+        self.cut(false, index)
+    }
+
+    fn weak_upperbound(&mut self, ub: &T) -> Result<IntVariableState, VariableError> {
+        let index = self.rank(ub, true);
+        self.cut(false, index)
+    }
+
+    fn strict_lowerbound(&mut self, lb: &T) -> Result<IntVariableState, VariableError> {
+        let index = self.rank(lb, true);
+        self.cut(true, index)
+    }
+
+    fn weak_lowerbound(&mut self, lb: &T) -> Result<IntVariableState, VariableError> {
+        let index = self.rank(lb, false);
+        self.cut(true, index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_by_key() {
+        let mut var =
+            IntVarValuesBy::new_from_values_by(vec![-3, 1, 2, 1], |v: &i32| v.abs()).unwrap();
+        assert_eq!(var.as_slice(), &[1, 2, -3]);
+        assert_eq!((var.min(), var.max()), (Some(&1), Some(&-3)));
+        assert_eq!(
+            var.strict_upperbound(&-3),
+            Ok(IntVariableState::MaxBoundChange)
+        );
+        assert_eq!(var.as_slice(), &[1, 2]);
+        assert_eq!(
+            var.weak_lowerbound(&-2),
+            Ok(IntVariableState::MinBoundChange)
+        );
+        assert_eq!(var.value(), Some(&2));
+        assert_eq!(
+            var.strict_lowerbound(&2),
+            Err(VariableError::BoundWipeout { lower: true })
+        );
+    }
+
+    #[test]
+    fn test_key_ties() {
+        let abs = |v: &i32| v.abs();
+        let mut var = IntVarValuesBy::new_from_values_by(vec![2, -2], abs).unwrap();
+        assert_eq!(var.as_slice(), &[-2, 2]);
+        assert_eq!(
+            var.strict_upperbound(&2),
+            Ok(IntVariableState::MaxBoundChange)
+        );
+        assert_eq!(var.as_slice(), &[-2]);
+
+        let mut var = IntVarValuesBy::new_from_values_by(vec![-2, 2, 3], abs).unwrap();
+        assert_eq!(
+            var.weak_upperbound(&-2),
+            Ok(IntVariableState::MaxBoundChange)
+        );
+        assert_eq!(var.as_slice(), &[-2]);
+
+        let mut var = IntVarValuesBy::new_from_values_by(vec![1, -2, 2], abs).unwrap();
+        assert_eq!(
+            var.strict_lowerbound(&-2),
+            Ok(IntVariableState::MinBoundChange)
+        );
+        assert_eq!(var.as_slice(), &[2]);
+
+        let mut var = IntVarValuesBy::new_from_values_by(vec![1, -2, 2], abs).unwrap();
+        assert_eq!(
+            var.weak_lowerbound(&2),
+            Ok(IntVariableState::MinBoundChange)
+        );
+        assert_eq!(var.as_slice(), &[2]);
+        assert_eq!(var.weak_lowerbound(&-2), Ok(IntVariableState::NoChange));
+    }
+}
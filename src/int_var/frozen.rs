@@ -0,0 +1,103 @@
+use super::IntVarValues;
+use crate::domains::{FiniteDomain, IterableDomain};
+use crate::Variable;
+
+/// Read-only view over an `IntVarValues`. Only the traits that do not modify the domain are
+/// implemented, so a `FrozenVar` can be handed to heuristics without risking any pruning.
+///
+/// ```compile_fail
+/// use crusp_variables::domains::{FromValuesDomain, OrderedDomain};
+/// use crusp_variables::int_var::IntVarValues;
+///
+/// let var = IntVarValues::new_from_values(vec![1, 2, 3]).unwrap();
+/// let mut frozen = var.freeze();
+/// frozen.weak_upperbound(&2);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FrozenVar<'a, T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    var: &'a IntVarValues<T>,
+}
+
+impl<T> IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    /// Returns a read-only view over the variable.
+    pub fn freeze(&self) -> FrozenVar<'_, T> {
+        FrozenVar { var: self }
+    }
+}
+
+impl<'a, T> FrozenVar<'a, T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    /// Returns the minimal value of the domain.
+    pub fn min(&self) -> Option<&'a T> {
+        self.var.as_slice().first()
+    }
+
+    /// Returns the maximal value of the domain.
+    pub fn max(&self) -> Option<&'a T> {
+        self.var.as_slice().last()
+    }
+
+    /// Returns the domain as a sorted slice without duplicates.
+    pub fn as_slice(&self) -> &'a [T] {
+        self.var.as_slice()
+    }
+}
+
+impl<'a, T> Variable<T> for FrozenVar<'a, T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn is_affected(&self) -> bool {
+        self.var.is_affected()
+    }
+
+    fn value(&self) -> Option<&T> {
+        self.var.value()
+    }
+}
+
+impl<'a, T> FiniteDomain<T> for FrozenVar<'a, T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn size(&self) -> usize {
+        self.var.size()
+    }
+}
+
+impl<'a, T> IterableDomain<T> for FrozenVar<'a, T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn iter<'b>(&'b self) -> Box<dyn Iterator<Item = &'b T> + 'b> {
+        self.var.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::IntVarValues;
+    use crate::domains::{FiniteDomain, FromValuesDomain, IterableDomain};
+    use crate::Variable;
+
+    #[test]
+    fn test_freeze() {
+        let var = IntVarValues::new_from_values(vec![3, 1, 2]).unwrap();
+        let frozen = var.freeze();
+        assert_eq!(frozen.min(), Some(&1));
+        assert_eq!(frozen.max(), Some(&3));
+        assert_eq!(frozen.size(), 3);
+        assert!(!frozen.is_affected());
+        assert_eq!(frozen.value(), None);
+        assert_eq!(frozen.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(frozen.as_slice(), var.as_slice());
+    }
+}
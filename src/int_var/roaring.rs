@@ -0,0 +1,74 @@
+use super::IntVarValues;
+use roaring::RoaringBitmap;
+
+// Order-preserving mapping of `i32` onto `u32` (i.e. `value - i32::MIN`).
+const SIGN_BIT: u32 = 1 << 31;
+
+impl IntVarValues<u32> {
+    /// Returns the domain as a `RoaringBitmap`.
+    pub fn to_roaring(&self) -> RoaringBitmap {
+        self.as_slice().iter().copied().collect()
+    }
+
+    /// Returns a new variable whose domain is the set of values of `bitmap`, or `None` if the
+    /// bitmap is empty.
+    ///
+    /// # Parameters
+    /// * `bitmap` - The values of the domain.
+    pub fn from_roaring(bitmap: &RoaringBitmap) -> Option<Self> {
+        if bitmap.is_empty() {
+            None
+        } else {
+            Some(IntVarValues::from_sorted_vec(bitmap.iter().collect()))
+        }
+    }
+}
+
+impl IntVarValues<i32> {
+    /// Returns the domain as a `RoaringBitmap` where each value `v` is stored as
+    /// `v - i32::MIN`, so that the order of the values is kept.
+    pub fn to_roaring(&self) -> RoaringBitmap {
+        self.as_slice()
+            .iter()
+            .map(|&v| v as u32 ^ SIGN_BIT)
+            .collect()
+    }
+
+    /// Returns a new variable from a bitmap built by `to_roaring`, or `None` if the bitmap is
+    /// empty.
+    ///
+    /// # Parameters
+    /// * `bitmap` - The values of the domain shifted by `-i32::MIN`.
+    pub fn from_roaring(bitmap: &RoaringBitmap) -> Option<Self> {
+        if bitmap.is_empty() {
+            None
+        } else {
+            Some(IntVarValues::from_sorted_vec(
+                bitmap.iter().map(|v| (v ^ SIGN_BIT) as i32).collect(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::FromValuesDomain;
+
+    #[test]
+    fn test_roaring_round_trip() {
+        let var = IntVarValues::new_from_values(vec![0u32, 3, 4, 5, 1 << 20, u32::MAX]).unwrap();
+        let bitmap = var.to_roaring();
+        assert_eq!(bitmap.len(), 6);
+        assert_eq!(IntVarValues::<u32>::from_roaring(&bitmap), Some(var));
+
+        let var = IntVarValues::new_from_values(vec![i32::MIN, -7, -1, 0, 2, i32::MAX]).unwrap();
+        let bitmap = var.to_roaring();
+        assert_eq!(bitmap.min(), Some(0));
+        assert_eq!(IntVarValues::<i32>::from_roaring(&bitmap), Some(var));
+        assert_eq!(
+            IntVarValues::<u32>::from_roaring(&RoaringBitmap::new()),
+            None
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::ops::{Deref, Range};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Source of the generations, shared by all the stores so that two stores never get the same
+// generation from two different modifications.
+static GENERATIONS: AtomicU64 = AtomicU64::new(0);
+
+fn next_generation() -> u64 {
+    GENERATIONS.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+/// Sorted values of a domain. The values before `start` are logically removed, so that removing
+/// the smallest values (e.g. when tightening the lower bound) does not shift the others.
+/// `generation` is replaced by a fresh one, unique across all the stores, on each modification
+/// of the values.
+pub(crate) struct ValueStore<T> {
+    values: Vec<T>,
+    start: usize,
+    generation: u64,
+}
+
+impl<T> ValueStore<T>
+where
+    T: Copy,
+{
+    /// Removes the values at the positions of `range`. Removing a prefix is done in constant
+    /// time.
+    pub(crate) fn remove_range(&mut self, range: Range<usize>) {
+        if !range.is_empty() {
+            self.generation = next_generation();
+        }
+        if range.start == 0 {
+            self.start += range.end;
+        } else if range.end == self.len() {
+            self.values.truncate(self.start + range.start);
+        } else {
+            self.values
+                .drain(self.start + range.start..self.start + range.end);
+        }
+        if self.start == self.values.len() {
+            self.values.clear();
+            self.start = 0;
+        }
+    }
+
+    /// Keeps the values that satisfy `keep`.
+    pub(crate) fn retain<F>(&mut self, keep: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.compact();
+        let len = self.values.len();
+        self.values.retain(keep);
+        if self.values.len() != len {
+            self.generation = next_generation();
+        }
+    }
+
+    /// Inserts `value` at the position `index`.
+    pub(crate) fn insert(&mut self, index: usize, value: T) {
+        self.values.insert(self.start + index, value);
+        self.generation = next_generation();
+    }
+
+    /// Replaces the values by those of `values`, which receives the previous storage.
+    /// `values` has to be a subset of the current values.
+    pub(crate) fn swap_vec(&mut self, values: &mut Vec<T>) {
+        self.compact();
+        if self.values.len() != values.len() {
+            self.generation = next_generation();
+        }
+        std::mem::swap(&mut self.values, values);
+    }
+
+    /// Replaces the values by `values`.
+    pub(crate) fn replace(&mut self, values: Vec<T>) {
+        self.values = values;
+        self.start = 0;
+        self.generation = next_generation();
+    }
+
+    /// Returns the generation of the values. A clone keeps the generation of its source until
+    /// one of them is modified.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // Drops the logically removed values.
+    fn compact(&mut self) {
+        self.values.drain(..self.start);
+        self.start = 0;
+    }
+}
+
+impl<T> Deref for ValueStore<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.values[self.start..]
+    }
+}
+
+impl<T> From<Vec<T>> for ValueStore<T> {
+    fn from(values: Vec<T>) -> ValueStore<T> {
+        ValueStore {
+            values,
+            start: 0,
+            generation: next_generation(),
+        }
+    }
+}
+
+// Only the remaining values are cloned.
+impl<T> Clone for ValueStore<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> ValueStore<T> {
+        ValueStore {
+            generation: self.generation,
+            ..ValueStore::from(self.to_vec())
+        }
+    }
+}
+
+impl<T> PartialEq for ValueStore<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &ValueStore<T>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T> Eq for ValueStore<T> where T: Eq {}
+
+impl<T> std::fmt::Debug for ValueStore<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValueStore;
+
+    #[test]
+    fn test_remove_range() {
+        let mut store = ValueStore::from(vec![1, 2, 3, 4, 5, 6]);
+        store.remove_range(0..2);
+        assert_eq!(&*store, &[3, 4, 5, 6]);
+        store.remove_range(1..2);
+        assert_eq!(&*store, &[3, 5, 6]);
+        store.remove_range(2..3);
+        assert_eq!(&*store, &[3, 5]);
+        store.insert(1, 4);
+        assert_eq!(&*store, &[3, 4, 5]);
+        store.retain(|v| v % 2 == 1);
+        assert_eq!(&*store, &[3, 5]);
+        assert_eq!(store.clone(), ValueStore::from(vec![3, 5]));
+        store.remove_range(0..2);
+        assert!(store.is_empty());
+    }
+}
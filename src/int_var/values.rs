@@ -1,28 +1,69 @@
-use super::IntVariableState;
+use super::storage::ValueStore;
+use super::{BranchStrategy, IntVariableState};
+use crate::bool_var::BoolVar;
 use crate::domains::{
     AssignableDomain, EqualDomain, FiniteDomain, FromRangeDomain, FromValuesDomain, IterableDomain,
-    OrderedDomain, OrderedPrunableDomain, PrunableDomain,
+    OrderedDomain, OrderedPrunableDomain, PrunableDomain, Reversed,
 };
 #[cfg(feature = "observer")]
 use crate::domains::{
     AssignableDomainObserver, EqualDomainObserver, OrderedDomainObserver,
     OrderedPrunableDomainObserver, PrunableDomainObserver,
 };
+use crate::{ArrayOfVariables, Variable, VariableError};
 #[cfg(feature = "observer")]
 use crate::{CruspVariable, VariableObserver};
-use crate::{Variable, VariableError};
+#[cfg(feature = "observer")]
 use crusp_core::VariableId;
 use crusp_core::{unwrap_first, unwrap_last};
-use num::One;
+use num::traits::CheckedRem;
+use num::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Integer, One, ToPrimitive, Zero};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::ops::ControlFlow;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct IntVarValues<T>
 where
     T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
 {
-    domain: Vec<T>,
+    domain: ValueStore<T>,
+    #[cfg(feature = "trail")]
+    trail: Vec<TrailLevel<T>>,
+}
+
+// Changes of the domain since a `push_level`: the removed values, unless an operation added
+// values, in which case the domain is saved before the addition.
+#[cfg(feature = "trail")]
+#[derive(Clone, Debug)]
+struct TrailLevel<T> {
+    removed: Vec<T>,
+    snapshot: Option<Vec<T>>,
+}
+
+#[cfg(feature = "trail")]
+impl<T> Default for TrailLevel<T> {
+    fn default() -> Self {
+        TrailLevel {
+            removed: vec![],
+            snapshot: None,
+        }
+    }
+}
+
+// Two variables are equal iff they have the same domain, whatever their trail.
+impl<T> PartialEq for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn eq(&self, other: &IntVarValues<T>) -> bool {
+        self.domain == other.domain
+    }
 }
 
+impl<T> Eq for IntVarValues<T> where T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd {}
+
+#[cfg(feature = "observer")]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CruspIntVarValues<T>
 where
@@ -32,6 +73,44 @@ where
     domain: Vec<T>,
 }
 
+/// Aggregates of the values of a domain, see `IntVarValues::summary`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DomainSummary<T> {
+    /// The minimal value.
+    pub min: T,
+    /// The maximal value.
+    pub max: T,
+    /// The number of values.
+    pub count: usize,
+    /// The sum of the values.
+    pub sum: T,
+}
+
+/// Pruning applied by `IntVarValues::try_prune`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PruneOp<T> {
+    /// Assigns the variable to the value.
+    SetValue(T),
+    /// Removes the value.
+    RemoveValue(T),
+    /// Removes the values lower than the value.
+    WeakLowerbound(T),
+    /// Removes the values greater than the value.
+    WeakUpperbound(T),
+}
+
+/// Reason why `IntVarValues::try_prune` did not apply a pruning.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PruneConflict {
+    /// The domain has been modified since the expected generation.
+    Stale {
+        /// The current generation of the domain.
+        current: u64,
+    },
+    /// The pruning failed.
+    Error(VariableError),
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct IntVarValuesBuilder<T>
 where
@@ -62,10 +141,49 @@ where
         }
     }
 
-    pub fn finalize(self) -> IntVarValues<T> {
-        IntVarValues {
-            domain: self.domain,
+    /// Removes `value` from the domain being built.
+    ///
+    /// # Parameters
+    /// * `value` - The excluded value.
+    pub fn without_value(mut self, value: T) -> Self {
+        if let Ok(index) = self.domain.binary_search(&value) {
+            self.domain.remove(index);
+        }
+        self
+    }
+
+    /// Removes `values` from the domain being built.
+    ///
+    /// # Parameters
+    /// * `values` - The excluded values.
+    pub fn without_values<Values>(mut self, values: Values) -> Self
+    where
+        Values: IntoIterator<Item = T>,
+    {
+        let mut excluded: Vec<_> = values.into_iter().collect();
+        excluded.sort_unstable();
+        self.domain.retain(|v| excluded.binary_search(v).is_err());
+        self
+    }
+
+    /// Removes the values of `[min;max]` from the domain being built.
+    ///
+    /// # Parameters
+    /// * `min` - The lower limit of the excluded range.
+    /// * `max` - The upper limit of the excluded range.
+    pub fn without_range(mut self, min: T, max: T) -> Self {
+        let start = self.domain.partition_point(|v| *v < min);
+        let end = self.domain.partition_point(|v| *v <= max);
+        if start < end {
+            self.domain.drain(start..end);
         }
+        self
+    }
+
+    /// Returns the built variable, which is empty (wiped out) if all the values have been
+    /// excluded.
+    pub fn finalize(self) -> IntVarValues<T> {
+        IntVarValues::from_sorted_vec(self.domain)
     }
 }
 
@@ -90,747 +208,2250 @@ where
                 domain.push(val);
                 val = val + one;
             }
-            Some(IntVarValues { domain })
+            Some(IntVarValues::from_sorted_vec(domain))
         }
     }
 
-    fn invalidate(&mut self) {
-        self.domain.clear();
-    }
-
-    fn domain_change(
-        &mut self,
-        prev_min: T,
-        prev_max: T,
-        prev_size: usize,
-    ) -> Result<IntVariableState, VariableError> {
-        if self.domain.is_empty() {
-            self.invalidate();
-            Err(VariableError::DomainWipeout)
-        } else if self.size() == prev_size {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_min() != prev_min || *self.unchecked_max() != prev_max {
-            Ok(IntVariableState::BoundsChange)
-        } else {
-            Ok(IntVariableState::ValuesChange)
-        }
+    /// Returns a new variable fixed to `c`.
+    ///
+    /// # Parameters
+    /// * `c` - The value of the variable.
+    pub fn constant(c: T) -> Self {
+        IntVarValues::from_sorted_vec(vec![c])
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    /*pub fn try_new<U>(min: U, max: U) -> Option<IntVarValues<U>>
-        where
-            U: Copy + Clone + Eq + PartialEq + Ord + PartialOrd + std::ops::Add<Output = U> + One,
-        {
-            if min > max {
-                None
-            } else {
-                let one = U::one();
-                let mut val = min;
-                let mut domain = vec![];
-                while val < max + one {
-                    domain.push(val);
-                    val = val + one;
-                }
-                Some(IntVarValues { domain })
-            }
-        }
-    */
-    fn invalidate(&mut self) {
-        self.domain.clear();
+    /// Returns the domain as a sorted slice without duplicates.
+    /// The slice is only valid until the next mutation of the variable.
+    pub fn as_slice(&self) -> &[T] {
+        &self.domain
     }
 
-    fn domain_change<Observer>(
+    /// Removes every value of the domain that has no support in `other`, that is every value
+    /// `v` such that there is no `w` in `other` satisfying `rel(v, w)`.
+    ///
+    /// # Parameters
+    /// * `other` - The variable providing the supports.
+    /// * `rel` - The relation between a value of `self` and a value of `other`.
+    pub fn retain_supported<F>(
         &mut self,
-        observer: &mut Observer,
-        prev_min: T,
-        prev_max: T,
-        prev_size: usize,
+        other: &IntVarValues<T>,
+        rel: F,
     ) -> Result<IntVariableState, VariableError>
     where
-        Observer: VariableObserver<IntVariableState>,
+        F: Fn(&T, &T) -> bool,
     {
-        if self.domain.is_empty() {
-            self.invalidate();
-            observer.push_error(self.id, VariableError::DomainWipeout)
-        } else if self.size() == prev_size {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_min() != prev_min || *self.unchecked_max() != prev_max {
-            observer.push_change(self.id, IntVariableState::BoundsChange)
-        } else {
-            observer.push_change(self.id, IntVariableState::ValuesChange)
-        }
+        self.retains_if(|v| other.domain.iter().any(|w| rel(v, w)))
     }
-}
 
-impl<T> IterableDomain<T> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &T> + 'a> {
-        Box::new(self.domain.iter())
+    /// Returns the `k` smallest values of the domain (or the whole domain if `k` is greater
+    /// than its size).
+    pub fn smallest_k(&self, k: usize) -> &[T] {
+        &self.domain[..k.min(self.domain.len())]
     }
-}
 
-impl<T> FromRangeDomain<T> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd + std::ops::Add<Output = T> + One,
-{
-    fn new_from_range(min: T, max: T) -> Option<IntVarValues<T>> {
-        if min > max {
-            None
-        } else {
-            let one = T::one();
-            let mut val = min;
-            let mut domain = vec![];
-            while val < max + one {
-                domain.push(val);
-                val = val + one;
-            }
-            Some(IntVarValues { domain })
+    /// Returns the `k` largest values of the domain (or the whole domain if `k` is greater
+    /// than its size).
+    pub fn largest_k(&self, k: usize) -> &[T] {
+        &self.domain[self.domain.len().saturating_sub(k)..]
+    }
+
+    /// Splits the domain at its midpoint index and returns both halves as new variables, or
+    /// `None` if the domain has less than two values. The variable itself is left unchanged.
+    pub fn bisect(&self) -> Option<(IntVarValues<T>, IntVarValues<T>)> {
+        if self.domain.len() < 2 {
+            return None;
         }
+        let (lower, upper) = self.domain.split_at(self.domain.len() / 2);
+        Some((
+            IntVarValues::from_sorted_vec(lower.to_vec()),
+            IntVarValues::from_sorted_vec(upper.to_vec()),
+        ))
     }
-}
 
-impl<T> FromValuesDomain<T> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn new_from_values<Values>(values: Values) -> Option<IntVarValues<T>>
+    /// Returns the domain of `y = x / k` (truncating division) where `x` is `self`.
+    /// Returns an error of type `VariableError::InvalidArgument` if `k` is zero, or of type
+    /// `VariableError::Overflow` if a quotient does not fit in `T` (e.g. `i32::MIN / -1`).
+    ///
+    /// # Parameters
+    /// * `k` - The divisor.
+    pub fn divided_by(&self, k: T) -> Result<IntVarValues<T>, VariableError>
     where
-        Values: IntoIterator<Item = T>,
+        T: CheckedDiv + Zero,
     {
-        let mut domain = values.into_iter().collect::<Vec<_>>();
+        if k.is_zero() {
+            return Err(VariableError::InvalidArgument);
+        }
+        let mut domain = self
+            .domain
+            .iter()
+            .map(|v| v.checked_div(&k))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(VariableError::Overflow)?;
         domain.sort();
         domain.dedup();
-        if domain.is_empty() {
-            None
-        } else {
-            Some(IntVarValues { domain })
-        }
+        Ok(IntVarValues::from_sorted_vec(domain))
     }
-}
 
-impl<T> AssignableDomain<T, IntVariableState> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn set_value(&mut self, value: T) -> Result<IntVariableState, VariableError> {
-        if *self.unchecked_min() > value || *self.unchecked_max() < value {
-            //self.invalidate();
-            return Err(VariableError::DomainWipeout);
+    /// Returns the domain of `y = x % k` where `x` is `self`.
+    /// As for Rust's `%`, the residue of a negative value is negative or zero (e.g. `-4 % 3`
+    /// is `-1`) and the sign of `k` is ignored. The residue of `T::MIN` modulo `-1` is `0`.
+    /// Returns an error of type `VariableError::InvalidArgument` if `k` is zero.
+    ///
+    /// # Parameters
+    /// * `k` - The modulus.
+    pub fn modulo(&self, k: T) -> Result<IntVarValues<T>, VariableError>
+    where
+        T: CheckedRem + Zero,
+    {
+        if k.is_zero() {
+            return Err(VariableError::InvalidArgument);
         }
-        let var_value = self.value();
-        match var_value {
-            Some(var_value) if *var_value == value => Ok(IntVariableState::NoChange),
-            _ => {
-                let found_value = self.domain.binary_search(&value);
-                match found_value {
-                    Ok(_) => {
-                        self.domain = vec![value];
-                        Ok(IntVariableState::BoundsChange)
-                    }
-                    _ => {
-                        self.invalidate();
-                        Err(VariableError::DomainWipeout)
-                    }
+        // `checked_rem` only fails on `T::MIN % -1`, whose residue is 0.
+        let mut domain: Vec<_> = self
+            .domain
+            .iter()
+            .map(|v| v.checked_rem(&k).unwrap_or_else(T::zero))
+            .collect();
+        domain.sort();
+        domain.dedup();
+        Ok(IntVarValues::from_sorted_vec(domain))
+    }
+
+    /// Returns an iterator over the values of the domain, each value being tagged with `true`
+    /// if it is one of the bounds of the domain.
+    pub fn iter_tagged<'a>(&'a self) -> impl Iterator<Item = (&'a T, bool)> + 'a {
+        let last = self.domain.len().saturating_sub(1);
+        self.domain
+            .iter()
+            .enumerate()
+            .map(move |(index, v)| (v, index == 0 || index == last))
+    }
+
+    /// Returns a variable with an empty domain. Such a variable is in the wiped out state:
+    /// `value()` returns `None` and any bound query (e.g. `unchecked_min`) panics.
+    /// It is meant to be used as the starting point of algorithms that accumulate values
+    /// with `union` before checking the result.
+    pub fn empty() -> IntVarValues<T> {
+        IntVarValues::from_sorted_vec(vec![])
+    }
+
+    /// Returns if the domain of the variable is empty.
+    pub fn is_wiped(&self) -> bool {
+        self.domain.is_empty()
+    }
+
+    /// Adds the values of `other` to the domain of the variable.
+    ///
+    /// # Parameters
+    /// * `other` - The variable whose values are added.
+    pub fn union(&mut self, other: &IntVarValues<T>) {
+        self.save_before_growth();
+        self.domain
+            .replace(union_sorted(&self.domain, &other.domain));
+    }
+
+    /// Same as `retains_if` but also returns the number of values left in the domain.
+    ///
+    /// # Parameters
+    /// * `pred` - The predicate the kept values satisfy.
+    pub fn retains_if_count<Predicate>(
+        &mut self,
+        pred: Predicate,
+    ) -> Result<(IntVariableState, usize), VariableError>
+    where
+        Predicate: FnMut(&T) -> bool,
+    {
+        let state = self.retains_if(pred)?;
+        Ok((state, self.size()))
+    }
+
+    /// Returns a new variable whose domain contains the values that are in exactly one of the
+    /// two domains. The returned variable is wiped out if both domains are equal.
+    ///
+    /// # Parameters
+    /// * `other` - The variable to compare to.
+    pub fn symmetric_difference(&self, other: &IntVarValues<T>) -> IntVarValues<T> {
+        use std::cmp::Ordering;
+        let (lhs, rhs) = (&self.domain, &other.domain);
+        let mut domain = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < lhs.len() && j < rhs.len() {
+            match lhs[i].cmp(&rhs[j]) {
+                Ordering::Less => {
+                    domain.push(lhs[i]);
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    domain.push(rhs[j]);
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    i += 1;
+                    j += 1;
                 }
             }
         }
+        domain.extend_from_slice(&lhs[i..]);
+        domain.extend_from_slice(&rhs[j..]);
+        IntVarValues::from_sorted_vec(domain)
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> AssignableDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn set_value<Observer>(
+    /// Returns a view of the variable in which the order of the values is reversed.
+    pub fn reversed(self) -> Reversed<IntVarValues<T>> {
+        Reversed::new(self)
+    }
+
+    /// Removes the values `v` of the domain for which there is no `w` in `other` such that
+    /// `v * w` is in `product` (back-propagation of `product = self * other`).
+    /// A zero value is supported as soon as `product` contains zero, whatever `other` is.
+    /// A `w` for which `v * w` does not fit in `T` does not support `v`.
+    ///
+    /// # Parameters
+    /// * `other` - The other factor.
+    /// * `product` - The product of both factors.
+    pub fn retain_factor(
         &mut self,
-        observer: &mut Observer,
-        value: T,
+        other: &IntVarValues<T>,
+        product: &IntVarValues<T>,
     ) -> Result<IntVariableState, VariableError>
     where
-        Observer: VariableObserver<IntVariableState>,
+        T: CheckedMul + Zero,
     {
-        if *self.unchecked_min() > value || *self.unchecked_max() < value {
-            //self.invalidate();
-            return observer.push_error(self.id, VariableError::DomainWipeout);
-        }
-        let var_value = self.value();
-        match var_value {
-            Some(var_value) if *var_value == value => Ok(IntVariableState::NoChange),
-            _ => {
-                let found_value = self.domain.binary_search(&value);
-                match found_value {
-                    Ok(_) => {
-                        self.domain = vec![value];
-                        observer.push_change(self.id, IntVariableState::BoundsChange)
-                    }
-                    _ => {
-                        self.invalidate();
-                        observer.push_error(self.id, VariableError::DomainWipeout)
-                    }
-                }
+        let zero_in_product = product.domain.binary_search(&T::zero()).is_ok();
+        self.retains_if(|&v| {
+            if v.is_zero() {
+                zero_in_product
+            } else {
+                other.domain.iter().any(|w| {
+                    v.checked_mul(w)
+                        .is_some_and(|p| product.domain.binary_search(&p).is_ok())
+                })
+            }
+        })
+    }
+
+    /// Inserts `value` in the domain at its sorted position.
+    /// Returns `true` if the value has been inserted or `false` if it was already present.
+    ///
+    /// # Parameters
+    /// * `value` - The value to insert.
+    pub fn insert_value(&mut self, value: T) -> bool {
+        match self.domain.binary_search(&value) {
+            Ok(_) => false,
+            Err(index) => {
+                self.save_before_growth();
+                self.domain.insert(index, value);
+                true
             }
         }
     }
-}
 
-impl<T> Variable<T> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn is_affected(&self) -> bool {
-        self.domain.len() == 1
+    /// Returns `true` iff both variables are affected to the same value.
+    ///
+    /// # Parameters
+    /// * `other` - The variable to compare to.
+    pub fn definitely_equal(&self, other: &IntVarValues<T>) -> bool {
+        self.is_affected() && other.is_affected() && self.domain == other.domain
     }
 
-    fn value(&self) -> Option<&T> {
-        if self.min() == self.max() {
-            self.min()
-        } else {
-            None
+    /// Returns `true` iff the bounds of both variables overlap (the variables may be equal).
+    ///
+    /// # Parameters
+    /// * `other` - The variable to compare to.
+    pub fn possibly_equal(&self, other: &IntVarValues<T>) -> bool {
+        match (self.min(), self.max(), other.min(), other.max()) {
+            (Some(min), Some(max), Some(other_min), Some(other_max)) => {
+                min <= other_max && other_min <= max
+            }
+            _ => false,
         }
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> Variable<T> for CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn is_affected(&self) -> bool {
-        self.domain.len() == 1
+    /// Returns `true` iff the domain has no hole, that is iff `size() == max - min + 1`.
+    /// The check is done in constant time from the bounds and the size of the domain.
+    pub fn is_interval(&self) -> bool
+    where
+        T: ToPrimitive,
+    {
+        match (self.domain.first(), self.domain.last()) {
+            (Some(min), Some(max)) => match (min.to_i128(), max.to_i128()) {
+                (Some(min), Some(max)) => {
+                    max.checked_sub(min).map(|span| span as u128)
+                        == Some((self.domain.len() - 1) as u128)
+                }
+                _ => false,
+            },
+            _ => false,
+        }
     }
 
-    fn value(&self) -> Option<&T> {
-        if self.min() == self.max() {
-            self.min()
-        } else {
-            None
-        }
+    /// Posts the constraint `x != c`. Same as `remove_value`.
+    ///
+    /// ```
+    /// use crusp_variables::domains::FromValuesDomain;
+    /// use crusp_variables::int_var::IntVarValues;
+    ///
+    /// let mut x = IntVarValues::new_from_values(1..=3).unwrap();
+    /// x.not_equal_value(2).unwrap();
+    /// assert_eq!(x.as_slice(), &[1, 3]);
+    /// ```
+    ///
+    /// # Parameters
+    /// * `c` - The forbidden value.
+    pub fn not_equal_value(&mut self, c: T) -> Result<IntVariableState, VariableError> {
+        self.remove_value(c)
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> CruspVariable<T> for CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn id(&self) -> VariableId {
-        self.id
+    /// Posts the constraint `x == c`. Same as `set_value`.
+    ///
+    /// ```
+    /// use crusp_variables::domains::FromValuesDomain;
+    /// use crusp_variables::int_var::IntVarValues;
+    ///
+    /// let mut x = IntVarValues::new_from_values(1..=3).unwrap();
+    /// x.equal_value(2).unwrap();
+    /// assert_eq!(x.as_slice(), &[2]);
+    /// assert!(x.equal_value(3).is_err());
+    /// ```
+    ///
+    /// # Parameters
+    /// * `c` - The assigned value.
+    pub fn equal_value(&mut self, c: T) -> Result<IntVariableState, VariableError> {
+        self.set_value(c)
     }
-}
 
-impl<T> FiniteDomain<T> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn size(&self) -> usize {
-        self.domain.len()
+    /// Returns an owned copy of the domain, sorted and without duplicates.
+    ///
+    /// ```
+    /// use crusp_variables::domains::FromValuesDomain;
+    /// use crusp_variables::int_var::IntVarValues;
+    ///
+    /// let var = IntVarValues::new_from_values(vec![3, 1, 2, 1]).unwrap();
+    /// assert_eq!(var.to_vec(), vec![1, 2, 3]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<T> {
+        self.domain.to_vec()
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> FiniteDomain<T> for CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn size(&self) -> usize {
-        self.domain.len()
+    /// Returns an error of type `VariableError::DomainWipeout` if the domain has less than `k`
+    /// values. Meant to be called after a pruning when a constraint needs at least `k`
+    /// candidates.
+    ///
+    /// # Parameters
+    /// * `k` - The minimal number of values.
+    pub fn ensure_size_at_least(&mut self, k: usize) -> Result<(), VariableError> {
+        if self.domain.len() < k {
+            Err(VariableError::DomainWipeout)
+        } else {
+            Ok(())
+        }
     }
-}
 
-impl<T> OrderedDomain<T, IntVariableState> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn min(&self) -> Option<&T> {
-        self.domain.first()
+    /// Removes the values of the domain that are not yielded by `sorted_values`.
+    /// The iterator has to yield its values in ascending order (checked in debug builds), so
+    /// that the intersection is computed by a linear merge. Any domain iterator (e.g.
+    /// `other.iter().copied()`) satisfies this precondition.
+    ///
+    /// # Parameters
+    /// * `sorted_values` - The allowed values in ascending order.
+    pub fn retain_in_iter<I>(&mut self, sorted_values: I) -> Result<IntVariableState, VariableError>
+    where
+        I: Iterator<Item = T>,
+    {
+        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
+        let mut values = sorted_values.peekable();
+        let mut last: Option<T> = None;
+        self.retain_domain(|v| {
+            while let Some(&w) = values.peek() {
+                debug_assert!(last <= Some(w), "values are not sorted");
+                last = Some(w);
+                if w < *v {
+                    values.next();
+                } else {
+                    break;
+                }
+            }
+            values.peek() == Some(v)
+        });
+        self.domain_change(min, max, size)
     }
-    fn max(&self) -> Option<&T> {
-        self.domain.last()
+
+    /// Assigns the variable to `value` if it belongs to the domain. Unlike `set_value`, the
+    /// variable is left untouched (and not wiped out) when `value` is not in the domain.
+    /// Returns `true` iff the variable is affected to `value`.
+    ///
+    /// # Parameters
+    /// * `value` - The value to assign.
+    pub fn assign_if_possible(&mut self, value: T) -> bool {
+        if self.domain.binary_search(&value).is_err() {
+            return false;
+        }
+        if self.domain.len() > 1 {
+            self.shrink_to(vec![value]);
+        }
+        true
     }
 
-    fn strict_upperbound(&mut self, ub: &T) -> Result<IntVariableState, VariableError> {
-        if *self.unchecked_max() < *ub {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_min() >= *ub {
-            Err(VariableError::DomainWipeout)
-        } else {
-            let index = self.domain.iter().rposition(|&val| val < *ub).unwrap();
-            self.domain.truncate(index + 1);
-            Ok(IntVariableState::BoundsChange)
+    /// Keeps the value `i` iff the boolean `mask[i]` can still be true. Values that are not a
+    /// valid index of the mask are removed. This is the integer side of set channeling.
+    ///
+    /// # Parameters
+    /// * `mask` - The boolean variables indexed by the values of the domain.
+    pub fn retain_by_mask(
+        &mut self,
+        mask: &impl ArrayOfVariables<bool, BoolVar>,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        T: ToPrimitive,
+    {
+        self.retains_if(|v| {
+            v.to_usize()
+                .and_then(|index| mask.get(index))
+                .is_some_and(|b| b.size() > 0 && b.value() != Some(&false))
+        })
+    }
+
+    /// Returns the value of the domain that is the closest to `target`, the lower one in case of
+    /// tie, or `None` if the domain is empty.
+    ///
+    /// # Parameters
+    /// * `target` - The value to approach.
+    pub fn nearest(&self, target: T) -> Option<T>
+    where
+        T: CheckedSub,
+    {
+        match self.domain.binary_search(&target) {
+            Ok(_) => Some(target),
+            Err(index) => match (
+                self.domain.get(index.wrapping_sub(1)),
+                self.domain.get(index),
+            ) {
+                (Some(&lower), Some(&upper)) => {
+                    // A distance that does not fit in `T` is the larger one.
+                    match (upper.checked_sub(&target), target.checked_sub(&lower)) {
+                        (Some(above), Some(below)) if above < below => Some(upper),
+                        (Some(_), None) => Some(upper),
+                        _ => Some(lower),
+                    }
+                }
+                (lower, upper) => lower.or(upper).copied(),
+            },
         }
     }
 
-    fn weak_upperbound(&mut self, ub: &T) -> Result<IntVariableState, VariableError> {
-        if *self.unchecked_max() <= *ub {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_min() > *ub {
-            Err(VariableError::DomainWipeout)
-        } else {
-            let index = self.domain.iter().rposition(|&val| val <= *ub).unwrap();
-            self.domain.truncate(index + 1);
-            Ok(IntVariableState::BoundsChange)
+    /// Returns the greatest value of the domain lower than or equal to `target`, or `None` if
+    /// there is no such value.
+    ///
+    /// # Parameters
+    /// * `target` - The upper limit.
+    pub fn floor_value(&self, target: T) -> Option<T> {
+        match self.domain.binary_search(&target) {
+            Ok(_) => Some(target),
+            Err(index) => self.domain.get(index.wrapping_sub(1)).copied(),
         }
     }
 
-    fn strict_lowerbound(&mut self, lb: &T) -> Result<IntVariableState, VariableError> {
-        if *self.unchecked_min() > *lb {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_max() <= *lb {
-            Err(VariableError::DomainWipeout)
-        } else {
-            let index = self.domain.iter().position(|&val| val > *lb).unwrap();
-            self.domain.drain(0..index);
-            Ok(IntVariableState::BoundsChange)
+    /// Returns the smallest value of the domain greater than or equal to `target`, or `None`
+    /// if there is no such value.
+    ///
+    /// # Parameters
+    /// * `target` - The lower limit.
+    pub fn ceil_value(&self, target: T) -> Option<T> {
+        match self.domain.binary_search(&target) {
+            Ok(_) => Some(target),
+            Err(index) => self.domain.get(index).copied(),
         }
     }
 
-    fn weak_lowerbound(&mut self, lb: &T) -> Result<IntVariableState, VariableError> {
-        if *self.unchecked_min() >= *lb {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_max() < *lb {
+    /// Returns a new variable whose domain is the intersection of the inclusive ranges
+    /// `[min;max]`, or `None` if the intersection is empty or if there is no range.
+    ///
+    /// # Parameters
+    /// * `ranges` - The ranges `(min, max)` to intersect.
+    pub fn from_range_intersection(ranges: &[(T, T)]) -> Option<IntVarValues<T>>
+    where
+        T: std::ops::Add<Output = T> + One,
+    {
+        let (min, max) = ranges
+            .iter()
+            .copied()
+            .reduce(|(min, max), (lb, ub)| (min.max(lb), max.min(ub)))?;
+        IntVarValues::new_from_range(min, max)
+    }
+
+    /// Returns a new variable wrapping `values` without sorting them.
+    /// Returns an error of type `VariableError::InvalidArgument` if the values are not sorted
+    /// or contain duplicates, and an error of type `VariableError::DomainWipeout` if there is
+    /// no value.
+    ///
+    /// # Parameters
+    /// * `values` - The values of the domain in strictly increasing order.
+    pub fn from_vec_checked(values: Vec<T>) -> Result<IntVarValues<T>, VariableError> {
+        if values.is_empty() {
             Err(VariableError::DomainWipeout)
+        } else if values.windows(2).any(|pair| pair[0] >= pair[1]) {
+            Err(VariableError::InvalidArgument)
         } else {
-            let index = self.domain.iter().position(|&val| val >= *lb).unwrap();
-            self.domain.drain(0..index);
-            Ok(IntVariableState::BoundsChange)
+            Ok(IntVarValues::from_sorted_vec(values))
         }
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> OrderedDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn min(&self) -> Option<&T> {
-        self.domain.first()
-    }
-    fn max(&self) -> Option<&T> {
-        self.domain.last()
+    /// Returns the ratio between the size of the domain and its span `max - min + 1`: `1.0`
+    /// for a domain without hole, closer to `0.0` for sparse domains. Returns `0.0` for an
+    /// empty domain.
+    pub fn fragmentation(&self) -> f64
+    where
+        T: ToPrimitive,
+    {
+        match (self.domain.first(), self.domain.last()) {
+            (Some(min), Some(max)) => match (min.to_f64(), max.to_f64()) {
+                (Some(min), Some(max)) => self.domain.len() as f64 / (max - min + 1.0),
+                _ => 0.0,
+            },
+            _ => 0.0,
+        }
     }
 
-    fn strict_upperbound<Observer>(
+    /// Removes the values `v` such that `low <= v <= high`, where `None` stands for an
+    /// unbounded side (e.g. `(None, Some(3))` removes `(-inf;3]`).
+    ///
+    /// # Parameters
+    /// * `low` - The lower limit of the removed interval.
+    /// * `high` - The upper limit of the removed interval.
+    pub fn remove_open_interval(
         &mut self,
-        observer: &mut Observer,
-        ub: &T,
-    ) -> Result<IntVariableState, VariableError>
+        low: Option<T>,
+        high: Option<T>,
+    ) -> Result<IntVariableState, VariableError> {
+        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
+        let start = low.map_or(0, |low| self.domain.partition_point(|v| *v < low));
+        let end = high.map_or(size, |high| self.domain.partition_point(|v| *v <= high));
+        if start < end {
+            self.remove_range(start..end);
+        }
+        self.domain_change(min, max, size)
+    }
+
+    /// Returns the greatest common divisor of the values of the domain, the value itself for a
+    /// singleton, or `None` if the domain is empty.
+    pub fn domain_gcd(&self) -> Option<T>
     where
-        Observer: VariableObserver<IntVariableState>,
+        T: Integer,
     {
-        if *self.unchecked_max() < *ub {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_min() >= *ub {
-            observer.push_error(self.id, VariableError::DomainWipeout)
-        } else {
-            let index = self.domain.iter().rposition(|&val| val < *ub).unwrap();
-            self.domain.truncate(index + 1);
-            observer.push_change(self.id, IntVariableState::BoundsChange)
+        self.domain.iter().copied().reduce(|gcd, v| gcd.gcd(&v))
+    }
+
+    /// Adds `delta` to each value of the domain.
+    /// Returns an error of type `VariableError::Overflow` if a shifted value does not fit in
+    /// `T`, in which case the domain is left unchanged.
+    ///
+    /// # Parameters
+    /// * `delta` - The shift of the values.
+    pub fn checked_offset(&mut self, delta: T) -> Result<IntVariableState, VariableError>
+    where
+        T: CheckedAdd + Zero,
+    {
+        if delta.is_zero() {
+            return Ok(IntVariableState::NoChange);
         }
+        let domain = self
+            .domain
+            .iter()
+            .map(|v| v.checked_add(&delta))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(VariableError::Overflow)?;
+        self.save_before_growth();
+        self.domain.replace(domain);
+        Ok(IntVariableState::BoundsChange)
     }
 
-    fn weak_upperbound<Observer>(
-        &mut self,
-        observer: &mut Observer,
-        ub: &T,
-    ) -> Result<IntVariableState, VariableError>
+    /// Returns an iterator over the values of the domain in ascending order, by chunks of `n`
+    /// values (the last chunk may be shorter).
+    ///
+    /// # Panics
+    /// Panics if `n` is `0`.
+    ///
+    /// # Parameters
+    /// * `n` - The number of values of each chunk.
+    pub fn chunks<'a>(&'a self, n: usize) -> impl Iterator<Item = &'a [T]> + 'a {
+        self.domain.chunks(n)
+    }
+
+    /// Returns the number of values `v` of the domain such that `lo <= v <= hi`.
+    ///
+    /// # Parameters
+    /// * `lo` - The lower limit of the range.
+    /// * `hi` - The upper limit of the range.
+    pub fn count_in_range(&self, lo: T, hi: T) -> usize {
+        let start = self.domain.partition_point(|v| *v < lo);
+        let end = self.domain.partition_point(|v| *v <= hi);
+        end.saturating_sub(start)
+    }
+
+    /// Returns `true` iff every integer of `[lo;hi]` belongs to the domain (always `true` for
+    /// an empty range `lo > hi`).
+    ///
+    /// # Parameters
+    /// * `lo` - The lower limit of the range.
+    /// * `hi` - The upper limit of the range.
+    pub fn contains_range(&self, lo: T, hi: T) -> bool
     where
-        Observer: VariableObserver<IntVariableState>,
+        T: ToPrimitive,
     {
-        if *self.unchecked_max() <= *ub {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_min() > *ub {
-            observer.push_error(self.id, VariableError::DomainWipeout)
-        } else {
-            let index = self.domain.iter().rposition(|&val| val <= *ub).unwrap();
-            self.domain.truncate(index + 1);
-            observer.push_change(self.id, IntVariableState::BoundsChange)
+        if lo > hi {
+            return true;
         }
+        range_width(&lo, &hi) == Some(self.count_in_range(lo, hi) as u128)
     }
 
-    fn strict_lowerbound<Observer>(
-        &mut self,
-        observer: &mut Observer,
-        lb: &T,
-    ) -> Result<IntVariableState, VariableError>
+    /// Returns a new variable with the values of any iterable domain, or `None` if the domain
+    /// is empty.
+    ///
+    /// # Parameters
+    /// * `domain` - The domain to convert.
+    pub fn from_domain<D>(domain: &D) -> Option<IntVarValues<T>>
     where
-        Observer: VariableObserver<IntVariableState>,
+        D: IterableDomain<T>,
     {
-        if *self.unchecked_min() > *lb {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_max() <= *lb {
-            observer.push_error(self.id, VariableError::DomainWipeout)
-        } else {
-            let index = self.domain.iter().position(|&val| val > *lb).unwrap();
-            self.domain.drain(0..index);
-            observer.push_change(self.id, IntVariableState::BoundsChange)
+        IntVarValues::new_from_values(domain.iter().copied())
+    }
+
+    /// Returns the greatest value of the domain lower than or equal to the middle of the
+    /// bounds, or `None` if the domain is empty. The middle is computed as
+    /// `min + (max - min) / 2` so that it does not overflow for bounds close to `T::MAX`
+    /// (the span `max - min` itself has to fit in `T`).
+    pub fn select_midpoint(&self) -> Option<T>
+    where
+        T: std::ops::Sub<Output = T> + std::ops::Add<Output = T> + std::ops::Div<Output = T> + One,
+    {
+        let (min, max) = (*self.domain.first()?, *self.domain.last()?);
+        let two = T::one() + T::one();
+        self.floor_value(min + (max - min) / two)
+    }
+
+    /// Returns an iterator over the maximal runs of consecutive values of the domain, in
+    /// ascending order (e.g. `[1, 2], [5], [7, 8, 9]` for `{1,2,5,7,8,9}`).
+    pub fn runs<'a>(&'a self) -> impl Iterator<Item = &'a [T]> + 'a
+    where
+        T: std::ops::Add<Output = T> + One,
+    {
+        let mut rest = &self.domain[..];
+        std::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let len = 1 + rest
+                .windows(2)
+                .take_while(|pair| pair[0] + T::one() == pair[1])
+                .count();
+            let (run, tail) = rest.split_at(len);
+            rest = tail;
+            Some(run)
+        })
+    }
+
+    /// Restricts the domain to its longest run of consecutive values, the lowest one in case of
+    /// tie.
+    pub fn keep_largest_run(&mut self) -> Result<IntVariableState, VariableError>
+    where
+        T: std::ops::Add<Output = T> + One,
+    {
+        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
+        let mut start = 0;
+        let mut largest = 0..0;
+        for run in self.runs() {
+            if run.len() > largest.len() {
+                largest = start..start + run.len();
+            }
+            start += run.len();
         }
+        self.remove_range(largest.end..self.domain.len());
+        self.remove_range(0..largest.start);
+        self.domain_change(min, max, size)
     }
 
-    fn weak_lowerbound<Observer>(
-        &mut self,
-        observer: &mut Observer,
-        lb: &T,
-    ) -> Result<IntVariableState, VariableError>
+    /// Folds the values of the domain in ascending order, stopping as soon as `f` returns
+    /// `ControlFlow::Break`. Returns the value carried by the `Break`, or the final
+    /// accumulator if the fold went through all the values.
+    ///
+    /// # Parameters
+    /// * `init` - The initial accumulator.
+    /// * `f` - The folding function.
+    pub fn try_fold_values<B, F>(&self, init: B, f: F) -> B
     where
-        Observer: VariableObserver<IntVariableState>,
+        F: FnMut(B, &T) -> ControlFlow<B, B>,
     {
-        if *self.unchecked_min() >= *lb {
-            Ok(IntVariableState::NoChange)
-        } else if *self.unchecked_max() < *lb {
-            observer.push_error(self.id, VariableError::DomainWipeout)
-        } else {
-            let index = self.domain.iter().position(|&val| val >= *lb).unwrap();
-            self.domain.drain(0..index);
-            observer.push_change(self.id, IntVariableState::BoundsChange)
+        match self.domain.iter().try_fold(init, f) {
+            ControlFlow::Continue(acc) | ControlFlow::Break(acc) => acc,
         }
     }
-}
 
-impl<T> EqualDomain<T, IntVariableState> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    // Distinction between ValuesChange and BoundsChange
-    fn equal(
-        &mut self,
-        value: &mut Self,
-    ) -> Result<(IntVariableState, IntVariableState), VariableError> {
-        use std::collections::BTreeSet;
-        let s1: BTreeSet<_> = self.iter().copied().collect();
-        let s2: BTreeSet<_> = value.iter().copied().collect();
-        let domain: Vec<_> = s1.intersection(&s2).copied().collect();
+    /// Assigns the variable to its minimal value.
+    /// Returns an error of type `VariableError::DomainWipeout` if the domain is empty.
+    pub fn assign_min(&mut self) -> Result<IntVariableState, VariableError> {
+        match self.domain.first() {
+            Some(&min) => self.set_value(min),
+            None => Err(VariableError::DomainWipeout),
+        }
+    }
 
-        if domain.is_empty() {
+    /// Assigns the variable to its maximal value.
+    /// Returns an error of type `VariableError::DomainWipeout` if the domain is empty.
+    pub fn assign_max(&mut self) -> Result<IntVariableState, VariableError> {
+        match self.domain.last() {
+            Some(&max) => self.set_value(max),
+            None => Err(VariableError::DomainWipeout),
+        }
+    }
+
+    /// Removes the minimal value of the domain (the refutation of `x = min`) in constant time.
+    /// Returns an error of type `VariableError::DomainWipeout` if the variable was affected.
+    pub fn remove_min(&mut self) -> Result<IntVariableState, VariableError> {
+        if self.domain.len() <= 1 {
             self.invalidate();
-            value.invalidate();
             return Err(VariableError::DomainWipeout);
         }
-        let (ok_self, ok_value) = {
-            let check_change = |var: &mut IntVarValues<T>| {
-                if var.size() == domain.len() {
-                    IntVariableState::NoChange
-                } else if *var.unchecked_min() != unwrap_first!(domain)
-                    || *var.unchecked_max() != unwrap_last!(domain)
-                {
-                    IntVariableState::BoundsChange
-                } else {
-                    IntVariableState::ValuesChange
-                }
-            };
-            (check_change(self), check_change(value))
-        };
+        self.remove_range(0..1);
+        Ok(IntVariableState::MinBoundChange)
+    }
 
-        self.domain = domain.clone();
-        value.domain = domain;
-        Ok((ok_self, ok_value))
+    /// Removes the maximal value of the domain (the refutation of `x = max`) in constant time.
+    /// Returns an error of type `VariableError::DomainWipeout` if the variable was affected.
+    pub fn remove_max(&mut self) -> Result<IntVariableState, VariableError> {
+        if self.domain.len() <= 1 {
+            self.invalidate();
+            return Err(VariableError::DomainWipeout);
+        }
+        self.remove_range(self.domain.len() - 1..self.domain.len());
+        Ok(IntVariableState::MaxBoundChange)
     }
-    fn not_equal(
-        &mut self,
-        value: &mut IntVarValues<T>,
-    ) -> Result<(IntVariableState, IntVariableState), VariableError> {
-        match self.value() {
-            Some(val) => {
-                let ok_value = value.remove_value(*val)?;
-                Ok((IntVariableState::NoChange, ok_value))
+
+    /// Returns the value at the position `index` of the sorted domain, or `None` if `index` is
+    /// out of bounds.
+    ///
+    /// # Parameters
+    /// * `index` - The position of the value.
+    pub fn value_at(&self, index: usize) -> Option<T> {
+        self.domain.get(index).copied()
+    }
+
+    /// Returns the position of `value` in the sorted domain (the inverse of `value_at`), or
+    /// `None` if `value` is not in the domain.
+    ///
+    /// # Parameters
+    /// * `value` - The value to look for.
+    pub fn rank(&self, value: &T) -> Option<usize> {
+        self.domain.binary_search(value).ok()
+    }
+
+    /// Returns `true` iff both domains have at least one common value.
+    ///
+    /// # Parameters
+    /// * `other` - The variable to compare to.
+    pub fn intersects(&self, other: &IntVarValues<T>) -> bool {
+        use std::cmp::Ordering;
+        let (mut i, mut j) = (0, 0);
+        while i < self.domain.len() && j < other.domain.len() {
+            match self.domain[i].cmp(&other.domain[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => return true,
             }
-            _ => match value.value() {
-                Some(val) => {
-                    let ok_self = self.remove_value(*val)?;
-                    Ok((ok_self, IntVariableState::NoChange))
-                }
-                _ => Ok((IntVariableState::NoChange, IntVariableState::NoChange)),
-            },
         }
+        false
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> EqualDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    // Distinction between ValuesChange and BoundsChange
-    fn equal<Observer>(
-        &mut self,
-        observer: &mut Observer,
-        value: &mut Self,
-    ) -> Result<(IntVariableState, IntVariableState), VariableError>
+    /// Returns `true` iff both domains are disjoint and their union is exactly `[lo;hi]`.
+    ///
+    /// # Parameters
+    /// * `other` - The other part of the partition.
+    /// * `lo` - The lower limit of the range.
+    /// * `hi` - The upper limit of the range.
+    pub fn partitions_range(&self, other: &IntVarValues<T>, lo: T, hi: T) -> bool
     where
-        Observer: VariableObserver<IntVariableState>,
+        T: ToPrimitive,
     {
-        use std::collections::BTreeSet;
-        let s1: BTreeSet<_> = self.domain.iter().copied().collect();
-        let s2: BTreeSet<_> = value.domain.iter().copied().collect();
-        let domain: Vec<_> = s1.intersection(&s2).copied().collect();
-
-        if domain.is_empty() {
-            self.invalidate();
-            value.invalidate();
-            let _err = observer.push_error(self.id, VariableError::DomainWipeout);
-            let _err = observer.push_error(value.id, VariableError::DomainWipeout);
-            return Err(VariableError::DomainWipeout);
-        }
-        let (ok_self, ok_value) = {
-            let check_change = |var: &mut CruspIntVarValues<T>| {
-                if var.size() == domain.len() {
-                    IntVariableState::NoChange
-                } else if *var.unchecked_min() != unwrap_first!(domain)
-                    || *var.unchecked_max() != unwrap_last!(domain)
-                {
-                    IntVariableState::BoundsChange
-                } else {
-                    IntVariableState::ValuesChange
-                }
-            };
-            (check_change(self), check_change(value))
-        };
-
-        if ok_self != IntVariableState::NoChange {
-            let _change = observer.push_change(self.id, ok_self);
-        }
+        let inside = |var: &IntVarValues<T>| var.count_in_range(lo, hi) == var.size();
+        !self.intersects(other)
+            && inside(self)
+            && inside(other)
+            && range_width(&lo, &hi) == Some((self.size() + other.size()) as u128)
+    }
 
-        if ok_value != IntVariableState::NoChange {
-            let _change = observer.push_change(value.id, ok_value);
-        }
-        self.domain = domain.clone();
-        value.domain = domain;
-        Ok((ok_self, ok_value))
+    /// Returns `true` iff the variables can take pairwise distinct values, that is iff they
+    /// admit a system of distinct representatives (Hall's condition). The check computes a
+    /// maximum matching between the variables and the values with augmenting paths, starting
+    /// from the smallest domains.
+    ///
+    /// # Parameters
+    /// * `vars` - The variables that take distinct values.
+    pub fn has_system_of_distinct_representatives(vars: &[IntVarValues<T>]) -> bool {
+        let mut order: Vec<_> = (0..vars.len()).collect();
+        order.sort_by_key(|&index| vars[index].size());
+        let mut owners = BTreeMap::new();
+        order.into_iter().all(|index| {
+            let mut visited = BTreeSet::new();
+            augment_matching(vars, index, &mut visited, &mut owners)
+        })
     }
-    fn not_equal<Observer>(
+
+    /// Same as `in_sorted_values` but the intersection is built in `scratch`, which then
+    /// receives the previous storage of the domain. Reusing the same scratch buffer avoids an
+    /// allocation per call once its capacity is large enough.
+    /// The values have to be sorted in ascending order.
+    ///
+    /// # Parameters
+    /// * `values` - The allowed values in ascending order.
+    /// * `scratch` - The buffer used to build the new domain.
+    pub fn in_sorted_values_into<Values>(
         &mut self,
-        observer: &mut Observer,
-        value: &mut CruspIntVarValues<T>,
-    ) -> Result<(IntVariableState, IntVariableState), VariableError>
+        values: Values,
+        scratch: &mut Vec<T>,
+    ) -> Result<IntVariableState, VariableError>
     where
-        Observer: VariableObserver<IntVariableState>,
+        Values: IntoIterator<Item = T>,
     {
-        match self.value() {
-            Some(val) => {
-                let ok_value = value.remove_value(observer, *val)?;
-                Ok((IntVariableState::NoChange, ok_value))
+        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
+        let mut values = values.into_iter().peekable();
+        scratch.clear();
+        for &v in self.domain.iter() {
+            while values.next_if(|w| *w < v).is_some() {}
+            if values.peek() == Some(&v) {
+                scratch.push(v);
             }
-            _ => match value.value() {
-                Some(val) => {
-                    let ok_self = self.remove_value(observer, *val)?;
-                    let _change = observer.push_change(self.id, ok_self);
-                    Ok((ok_self, IntVariableState::NoChange))
-                }
-                _ => Ok((IntVariableState::NoChange, IntVariableState::NoChange)),
-            },
         }
+        self.swap_domain(scratch);
+        self.domain_change(min, max, size)
     }
-}
 
-impl<T> PrunableDomain<T, IntVariableState> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn in_values<Values>(&mut self, values: Values) -> Result<IntVariableState, VariableError>
-    where
-        Values: IntoIterator<Item = T>,
-    {
-        let values: Vec<_> = values.into_iter().collect();
-        let mut values: Vec<_> = values.into_iter().collect();
-        values.sort();
-        self.in_sorted_values(values.into_iter())
+    /// Same as `set_value` but also returns whether the variable is fixed after the update,
+    /// so that the caller knows the constraints over it are entailed.
+    /// See `Propagation` to turn the pair into a single outcome.
+    ///
+    /// # Parameters
+    /// * `value` - The value to assign.
+    pub fn set_value_entailing(
+        &mut self,
+        value: T,
+    ) -> Result<(IntVariableState, bool), VariableError> {
+        let state = self.set_value(value)?;
+        Ok((state, self.is_affected()))
     }
 
-    // check change function (equality, bounds, values, nochange...)
-    fn remove_value(&mut self, value: T) -> Result<IntVariableState, VariableError> {
-        if *self.unchecked_min() > value && *self.unchecked_max() < value {
-            return Ok(IntVariableState::NoChange);
-        }
-        let (min, max) = (self.min().copied(), self.max().copied());
-        let found_value = self.domain.binary_search(&value);
-        match found_value {
-            Ok(index) => {
-                self.domain.remove(index);
-                if self.size() == 0 {
-                    Err(VariableError::DomainWipeout)
-                } else if self.min().copied() != min || self.max().copied() != max {
-                    Ok(IntVariableState::BoundsChange)
-                } else {
-                    Ok(IntVariableState::ValuesChange)
+    /// Returns the values of the domain in the order given by `strategy`, for the search to
+    /// create one child per candidate. There is no candidate if the domain is empty.
+    ///
+    /// # Parameters
+    /// * `strategy` - The order of the candidates.
+    pub fn branch_candidates(&self, strategy: BranchStrategy) -> Box<dyn Iterator<Item = T> + '_> {
+        let domain = &self.domain[..];
+        match strategy {
+            BranchStrategy::MinFirst => Box::new(domain.iter().copied()),
+            BranchStrategy::MaxFirst => Box::new(domain.iter().rev().copied()),
+            BranchStrategy::Median => {
+                let median = domain.len().saturating_sub(1) / 2;
+                let mut positions: Vec<_> = (0..domain.len()).collect();
+                positions.sort_by_key(|&i| (i.abs_diff(median), i));
+                Box::new(positions.into_iter().map(move |i| domain[i]))
+            }
+            BranchStrategy::Bisect => {
+                let mut halves = VecDeque::new();
+                if !domain.is_empty() {
+                    halves.push_back(0..domain.len());
                 }
+                Box::new(std::iter::from_fn(move || {
+                    let half = halves.pop_front()?;
+                    let median = half.start + (half.len() - 1) / 2;
+                    if half.start < median {
+                        halves.push_back(half.start..median);
+                    }
+                    if median + 1 < half.end {
+                        halves.push_back(median + 1..half.end);
+                    }
+                    Some(domain[median])
+                }))
             }
-            _ => Ok(IntVariableState::NoChange),
         }
     }
 
-    fn remove_if<Predicate>(
+    /// Tightens the bounds of both variables to their common bounds, the values inside the
+    /// bounds being left untouched. Unlike `equal`, the domains may still differ afterwards.
+    /// The tightening is repeated until the bounds of both variables agree.
+    ///
+    /// # Parameters
+    /// * `other` - The variable whose bounds are synchronized.
+    pub fn equal_bounds_only(
         &mut self,
-        mut pred: Predicate,
-    ) -> Result<IntVariableState, VariableError>
+        other: &mut Self,
+    ) -> Result<(IntVariableState, IntVariableState), VariableError> {
+        let mut x = IntVariableState::NoChange;
+        let mut y = IntVariableState::NoChange;
+        while self.unchecked_min() != other.unchecked_min()
+            || self.unchecked_max() != other.unchecked_max()
+        {
+            let (min, max) = (*other.unchecked_min(), *other.unchecked_max());
+            x = x | self.weak_lowerbound(&min)? | self.weak_upperbound(&max)?;
+            let (min, max) = (*self.unchecked_min(), *self.unchecked_max());
+            y = y | other.weak_lowerbound(&min)? | other.weak_upperbound(&max)?;
+        }
+        Ok((x, y))
+    }
+
+    /// Returns a new variable whose values are the values of the domain plus `c`, the domain
+    /// being left untouched (see `checked_offset` for the in-place version).
+    /// Adding a constant keeps the values sorted, including for a negative `c` of a signed
+    /// type, so the domain is not sorted again.
+    /// Returns an error of type `VariableError::Overflow` if a shifted value does not fit in
+    /// `T`.
+    ///
+    /// # Parameters
+    /// * `c` - The constant added to the values.
+    pub fn plus_constant(&self, c: T) -> Result<IntVarValues<T>, VariableError>
     where
-        Predicate: FnMut(&T) -> bool,
+        T: CheckedAdd,
     {
-        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
-        self.domain.retain(|v| !pred(v));
-        self.domain_change(min, max, size)
+        self.domain
+            .iter()
+            .map(|v| v.checked_add(&c))
+            .collect::<Option<Vec<_>>>()
+            .map(IntVarValues::from_sorted_vec)
+            .ok_or(VariableError::Overflow)
     }
 
-    fn retains_if<Predicate>(
+    /// Returns the value of the variable if the domain is a singleton, otherwise `None`.
+    /// Same as `value` but with a single check of the size of the domain.
+    pub fn fixed_value(&self) -> Option<&T> {
+        if self.domain.len() == 1 {
+            self.domain.first()
+        } else {
+            None
+        }
+    }
+
+    /// Removes the values of `values` from the domain. The values are sorted first, so that
+    /// they are removed by a single linear pass over the domain instead of one `remove_value`
+    /// per value.
+    ///
+    /// # Parameters
+    /// * `values` - The removed values, in any order.
+    pub fn remove_values<Values>(
         &mut self,
-        mut pred: Predicate,
+        values: Values,
     ) -> Result<IntVariableState, VariableError>
     where
-        Predicate: FnMut(&T) -> bool,
+        Values: IntoIterator<Item = T>,
     {
+        let mut removed: Vec<_> = values.into_iter().collect();
+        removed.sort_unstable();
         let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
-        self.domain.retain(|v| pred(v));
+        let mut removed = removed.into_iter().peekable();
+        self.retain_domain(|v| {
+            while removed.next_if(|w| w < v).is_some() {}
+            removed.peek() != Some(v)
+        });
         self.domain_change(min, max, size)
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> PrunableDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    fn in_values<Observer, Values>(
-        &mut self,
-        observer: &mut Observer,
-        values: Values,
-    ) -> Result<IntVariableState, VariableError>
+    /// Renders the domain as a union of runs in the syntax of `parse_int_domain` (e.g.
+    /// `1..3, {5}, 7..9`), limited to the first `max_runs` runs. The number of runs left out
+    /// is appended as `…(+k more)`.
+    ///
+    /// # Parameters
+    /// * `max_runs` - The maximal number of rendered runs.
+    pub fn display_truncated(&self, max_runs: usize) -> String
     where
-        Observer: VariableObserver<IntVariableState>,
-        Values: IntoIterator<Item = T>,
+        T: std::ops::Add<Output = T> + One + std::fmt::Display,
     {
-        let values: Vec<_> = values.into_iter().collect();
-        let mut values: Vec<_> = values.into_iter().collect();
-        values.sort();
-        self.in_sorted_values(observer, values.into_iter())
+        let mut parts: Vec<_> = self
+            .runs()
+            .take(max_runs)
+            .map(|run| match run {
+                [value] => format!("{{{}}}", value),
+                _ => format!("{}..{}", run[0], run[run.len() - 1]),
+            })
+            .collect();
+        let more = self.runs().skip(max_runs).count();
+        if more > 0 {
+            parts.push(format!("…(+{} more)", more));
+        }
+        parts.join(", ")
     }
 
-    // check change function (equality, bounds, values, nochange...)
-    fn remove_value<Observer>(
-        &mut self,
-        observer: &mut Observer,
-        value: T,
-    ) -> Result<IntVariableState, VariableError>
+    /// Returns `true` iff the domain is the set of values of `values`, regardless of their
+    /// order and of duplicates.
+    ///
+    /// # Parameters
+    /// * `values` - The expected values.
+    pub fn equals_values(&self, values: &[T]) -> bool {
+        let mut values = values.to_vec();
+        values.sort_unstable();
+        values.dedup();
+        self.domain[..] == values[..]
+    }
+
+    /// Returns the number of values of the domain that satisfy `pred`, without modifying the
+    /// domain (i.e. the size of the domain after `retains_if(pred)`).
+    ///
+    /// # Parameters
+    /// * `pred` - The predicate counted values satisfy.
+    pub fn count_if<P>(&self, pred: P) -> usize
     where
-        Observer: VariableObserver<IntVariableState>,
+        P: Fn(&T) -> bool,
     {
-        if *self.unchecked_min() > value && *self.unchecked_max() < value {
-            return Ok(IntVariableState::NoChange);
-        }
-        let (min, max) = (self.min().copied(), self.max().copied());
-        let found_value = self.domain.binary_search(&value);
-        match found_value {
-            Ok(index) => {
-                self.domain.remove(index);
-                if self.size() == 0 {
-                    observer.push_error(self.id, VariableError::DomainWipeout)
-                } else if self.min().copied() != min || self.max().copied() != max {
-                    observer.push_change(self.id, IntVariableState::BoundsChange)
-                } else {
-                    observer.push_change(self.id, IntVariableState::ValuesChange)
-                }
-            }
-            _ => Ok(IntVariableState::NoChange),
+        self.domain.iter().filter(|v| pred(v)).count()
+    }
+
+    /// Keeps every `n`-th value of the domain, that is the values at the positions `0`, `n`,
+    /// `2n`, ... of the sorted domain.
+    /// Returns an error of type `VariableError::InvalidArgument` if `n` is `0`.
+    ///
+    /// # Parameters
+    /// * `n` - The step between two kept values.
+    pub fn decimate(&mut self, n: usize) -> Result<IntVariableState, VariableError> {
+        if n == 0 {
+            return Err(VariableError::InvalidArgument);
         }
+        self.retain_where_index(|rank, _| rank % n == 0)
     }
 
-    fn remove_if<Observer, Predicate>(
-        &mut self,
-        observer: &mut Observer,
-        mut pred: Predicate,
-    ) -> Result<IntVariableState, VariableError>
+    /// Returns the minimal value, the maximal value, the number of values and the sum of the
+    /// values of the domain, computed in a single pass, or `None` if the domain is empty.
+    /// The sum has to fit in `T`.
+    pub fn summary(&self) -> Option<DomainSummary<T>>
     where
-        Observer: VariableObserver<IntVariableState>,
-        Predicate: FnMut(&T) -> bool,
+        T: std::ops::Add<Output = T> + Default,
+    {
+        let (&min, &max) = (self.domain.first()?, self.domain.last()?);
+        let (count, sum) = self
+            .domain
+            .iter()
+            .fold((0, T::default()), |(count, sum), &v| (count + 1, sum + v));
+        Some(DomainSummary {
+            min,
+            max,
+            count,
+            sum,
+        })
+    }
+
+    /// Keeps the values that satisfy `f`, which receives the rank of each value in the sorted
+    /// domain along with the value. Same as `retains_if` when `f` ignores the rank.
+    ///
+    /// # Parameters
+    /// * `f` - The predicate over the rank and the value kept values satisfy.
+    pub fn retain_where_index<F>(&mut self, f: F) -> Result<IntVariableState, VariableError>
+    where
+        F: Fn(usize, &T) -> bool,
     {
         let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
-        self.domain.retain(|v| !pred(v));
-        self.domain_change(observer, min, max, size)
+        let mut rank = 0;
+        self.retain_domain(|v| {
+            rank += 1;
+            f(rank - 1, v)
+        });
+        self.domain_change(min, max, size)
     }
 
-    fn retains_if<Observer, Predicate>(
+    /// Returns the generation of the domain, which changes on each modification of the domain.
+    /// Two modifications never produce the same generation, even on different variables.
+    /// Clones share the generation of the variable they are cloned from until one of them is
+    /// modified.
+    pub fn generation(&self) -> u64 {
+        self.domain.generation()
+    }
+
+    /// Applies `op` only if the domain has not been modified since the generation
+    /// `expected_gen`, for optimistic concurrency between the holders of the variable.
+    /// Returns an error of type `PruneConflict::Stale` if the generation differs, in which
+    /// case the domain is left unchanged, or of type `PruneConflict::Error` if the pruning
+    /// fails.
+    ///
+    /// # Parameters
+    /// * `expected_gen` - The generation of the domain the pruning was computed from.
+    /// * `op` - The pruning to apply.
+    pub fn try_prune(
         &mut self,
-        observer: &mut Observer,
-        mut pred: Predicate,
+        expected_gen: u64,
+        op: PruneOp<T>,
+    ) -> Result<IntVariableState, PruneConflict> {
+        let current = self.generation();
+        if current != expected_gen {
+            return Err(PruneConflict::Stale { current });
+        }
+        match op {
+            PruneOp::SetValue(value) => self.set_value(value),
+            PruneOp::RemoveValue(value) => self.remove_value(value),
+            PruneOp::WeakLowerbound(lb) => self.weak_lowerbound(&lb),
+            PruneOp::WeakUpperbound(ub) => self.weak_upperbound(&ub),
+        }
+        .map_err(PruneConflict::Error)
+    }
+
+    /// Keeps the values of the form `start + k * step` for `k >= 0`, that is the values from
+    /// `start` onwards (downwards for a negative `step`) that are congruent to `start`
+    /// modulo `step`.
+    /// Returns an error of type `VariableError::InvalidArgument` if `step` is `0`.
+    ///
+    /// # Parameters
+    /// * `start` - The first term of the progression.
+    /// * `step` - The difference between two consecutive terms of the progression.
+    pub fn retain_arithmetic(
+        &mut self,
+        start: T,
+        step: T,
     ) -> Result<IntVariableState, VariableError>
     where
-        Observer: VariableObserver<IntVariableState>,
-        Predicate: FnMut(&T) -> bool,
+        T: Integer,
     {
+        if step.is_zero() {
+            return Err(VariableError::InvalidArgument);
+        }
         let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
-        self.domain.retain(|v| pred(v));
-        self.domain_change(observer, min, max, size)
+        let positive = step > T::zero();
+        // Every value is congruent modulo 1 and -1, and `T::MIN % -1` would overflow.
+        let unit = step.is_one() || (!positive && (step + T::one()).is_zero());
+        // Comparing the residues avoids computing `v - start`, which may not fit in `T`.
+        let residue = start.mod_floor(&step);
+        self.retain_domain(|&v| {
+            (v == start || (v > start) == positive) && (unit || v.mod_floor(&step) == residue)
+        });
+        self.domain_change(min, max, size)
     }
-}
 
-impl<T> OrderedPrunableDomain<T, IntVariableState> for IntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    // Change to non-naive implementation
-    fn in_sorted_values<Values>(
+    /// Value-consistent propagation of `z = x + y` on `x`: removes every value `v` of the domain
+    /// such that there is no `w` in `other` with `v + w` in `result`. The sums have to fit in
+    /// `T`.
+    ///
+    /// # Parameters
+    /// * `other` - The other term of the sum.
+    /// * `result` - The sum.
+    pub fn retain_sum_support(
         &mut self,
-        values: Values,
+        other: &IntVarValues<T>,
+        result: &IntVarValues<T>,
     ) -> Result<IntVariableState, VariableError>
     where
-        Values: IntoIterator<Item = T>,
+        T: std::ops::Add<Output = T>,
     {
-        use std::collections::BTreeSet;
-        let s1: BTreeSet<_> = self.iter().copied().collect();
-        let s2: BTreeSet<_> = values.into_iter().collect();
-        let domain: Vec<_> = s1.intersection(&s2).copied().collect();
+        self.retain_supported(other, |&v, &w| {
+            result.domain.binary_search(&(v + w)).is_ok()
+        })
+    }
 
-        if domain.is_empty() {
-            self.invalidate();
-            return Err(VariableError::DomainWipeout);
-        }
-        let ok_self = {
-            let check_change = |var: &mut IntVarValues<T>| {
-                if var.size() == domain.len() {
-                    IntVariableState::NoChange
-                } else if *var.unchecked_min() != unwrap_first!(domain)
-                    || *var.unchecked_max() != unwrap_last!(domain)
-                {
-                    IntVariableState::BoundsChange
-                } else {
-                    IntVariableState::ValuesChange
+    /// Opens a new level on the trail. The following changes of the domain can be undone
+    /// with `pop_level`.
+    #[cfg(feature = "trail")]
+    pub fn push_level(&mut self) {
+        self.trail.push(TrailLevel::default());
+    }
+
+    /// Undoes the changes of the domain since the last `push_level`. Returns `false` if there
+    /// is no open level.
+    #[cfg(feature = "trail")]
+    pub fn pop_level(&mut self) -> bool {
+        match self.trail.pop() {
+            Some(mut level) => {
+                if let Some(domain) = level.snapshot {
+                    self.domain.replace(domain);
                 }
-            };
-            check_change(self)
-        };
-        self.domain = domain;
-        Ok(ok_self)
+                level.removed.sort_unstable();
+                self.domain
+                    .replace(union_sorted(&self.domain, &level.removed));
+                true
+            }
+            None => false,
+        }
     }
-}
 
-#[cfg(feature = "observer")]
-impl<T> OrderedPrunableDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
-where
-    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
-{
-    // Change to non-naive implementation
-    fn in_sorted_values<Observer, Values>(
-        &mut self,
-        observer: &mut Observer,
-        values: Values,
-    ) -> Result<IntVariableState, VariableError>
+    // Removes the values at the positions of `range`, recording them on the trail.
+    fn remove_range(&mut self, range: std::ops::Range<usize>) {
+        #[cfg(feature = "trail")]
+        if let Some(level) = self.trail.last_mut().filter(|l| l.snapshot.is_none()) {
+            level.removed.extend_from_slice(&self.domain[range.clone()]);
+        }
+        self.domain.remove_range(range);
+    }
+
+    // Keeps the values that satisfy `keep`, recording the others on the trail.
+    fn retain_domain<F>(&mut self, mut keep: F)
     where
-        Observer: VariableObserver<IntVariableState>,
-        Values: IntoIterator<Item = T>,
+        F: FnMut(&T) -> bool,
     {
-        use std::collections::BTreeSet;
-        let s1: BTreeSet<_> = self.domain.iter().copied().collect();
-        let s2: BTreeSet<_> = values.into_iter().collect();
-        let domain: Vec<_> = s1.intersection(&s2).copied().collect();
-
-        if domain.is_empty() {
-            self.invalidate();
-            return observer.push_error(self.id, VariableError::DomainWipeout);
+        #[cfg(feature = "trail")]
+        if let Some(level) = self.trail.last_mut().filter(|l| l.snapshot.is_none()) {
+            self.domain.retain(|v| {
+                let kept = keep(v);
+                if !kept {
+                    level.removed.push(*v);
+                }
+                kept
+            });
+            return;
+        }
+        self.domain.retain(&mut keep);
+    }
+
+    // Replaces the domain by a subset of it, recording the removed values on the trail.
+    fn shrink_to(&mut self, mut domain: Vec<T>) {
+        self.swap_domain(&mut domain);
+    }
+
+    // Swaps the domain with a subset of it, recording the removed values on the trail.
+    fn swap_domain(&mut self, domain: &mut Vec<T>) {
+        #[cfg(feature = "trail")]
+        if let Some(level) = self.trail.last_mut().filter(|l| l.snapshot.is_none()) {
+            level
+                .removed
+                .extend(difference_sorted(&self.domain, domain));
+        }
+        self.domain.swap_vec(domain);
+    }
+
+    // Called before an operation that adds values to the domain: removals cannot undo it, so
+    // the current level falls back to a snapshot of the domain.
+    fn save_before_growth(&mut self) {
+        #[cfg(feature = "trail")]
+        if let Some(level) = self.trail.last_mut().filter(|l| l.snapshot.is_none()) {
+            level.snapshot = Some(self.domain.to_vec());
+        }
+    }
+
+    // The domain has to be sorted and without duplicates.
+    pub(super) fn from_sorted_vec(domain: Vec<T>) -> IntVarValues<T> {
+        IntVarValues {
+            domain: domain.into(),
+            #[cfg(feature = "trail")]
+            trail: vec![],
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.remove_range(0..self.domain.len());
+    }
+
+    fn domain_change(
+        &mut self,
+        prev_min: T,
+        prev_max: T,
+        prev_size: usize,
+    ) -> Result<IntVariableState, VariableError> {
+        if self.domain.is_empty() {
+            self.invalidate();
+            Err(VariableError::DomainWipeout)
+        } else if self.size() == prev_size {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_min() != prev_min || *self.unchecked_max() != prev_max {
+            Ok(IntVariableState::BoundsChange)
+        } else {
+            Ok(IntVariableState::ValuesChange)
+        }
+    }
+}
+
+impl IntVarValues<i64> {
+    /// Returns a new variable whose domain is `{offset + i : bit i of mask is set}`, or `None`
+    /// if `mask` is `0` or if a value does not fit in `i64`.
+    ///
+    /// # Parameters
+    /// * `mask` - The set of values shifted by `-offset`.
+    /// * `offset` - The value of the bit `0`.
+    pub fn from_bitmask(mask: u64, offset: i64) -> Option<Self> {
+        if mask == 0 {
+            return None;
+        }
+        let domain = (0..64)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| offset.checked_add(i))
+            .collect::<Option<Vec<_>>>()?;
+        Some(IntVarValues::from_sorted_vec(domain))
+    }
+
+    /// Returns the domain as a mask whose bit `i` is set iff `offset + i` is in the domain, or
+    /// `None` if a value is outside of `[offset;offset+63]`.
+    ///
+    /// # Parameters
+    /// * `offset` - The value of the bit `0`.
+    pub fn to_bitmask(&self, offset: i64) -> Option<u64> {
+        self.domain.iter().try_fold(0, |mask, &v| {
+            let i = v.checked_sub(offset).filter(|i| (0..64).contains(i))?;
+            Some(mask | 1 << i)
+        })
+    }
+}
+
+// State of a (non singleton) sorted domain reduced to `value`: only one bound moves when
+// `value` is the current min or max.
+fn assignment_state<T>(domain: &[T], value: T) -> IntVariableState
+where
+    T: Copy + Ord,
+{
+    if domain.last() == Some(&value) {
+        IntVariableState::MinBoundChange
+    } else if domain.first() == Some(&value) {
+        IntVariableState::MaxBoundChange
+    } else {
+        IntVariableState::BoundsChange
+    }
+}
+
+// Number of integers of `[lo;hi]` (`0` if `lo > hi`), or `None` if it does not fit in `u128`
+// (i.e. the whole `i128` range).
+fn range_width<T>(lo: &T, hi: &T) -> Option<u128>
+where
+    T: Ord + ToPrimitive,
+{
+    if lo > hi {
+        return Some(0);
+    }
+    let distance = match (lo.to_u128(), hi.to_u128()) {
+        (Some(min), Some(max)) => max - min,
+        // `hi - lo` fits in `u128`, so the wrapping difference is the exact distance.
+        _ => match (lo.to_i128(), hi.to_i128()) {
+            (Some(min), Some(max)) => max.wrapping_sub(min) as u128,
+            _ => return None,
+        },
+    };
+    distance.checked_add(1)
+}
+
+// Tries to match the variable `index` to a value, possibly rematching the owners of its
+// values along an augmenting path.
+fn augment_matching<T>(
+    vars: &[IntVarValues<T>],
+    index: usize,
+    visited: &mut BTreeSet<T>,
+    owners: &mut BTreeMap<T, usize>,
+) -> bool
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    for &value in vars[index].domain.iter() {
+        if visited.insert(value) {
+            let free = match owners.get(&value) {
+                Some(&owner) => augment_matching(vars, owner, visited, owners),
+                None => true,
+            };
+            if free {
+                owners.insert(value, index);
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// Linear merge of two sorted slices without duplicates.
+fn union_sorted<T>(lhs: &[T], rhs: &[T]) -> Vec<T>
+where
+    T: Copy + Ord,
+{
+    use std::cmp::Ordering;
+    let mut domain = Vec::with_capacity(lhs.len() + rhs.len());
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() && j < rhs.len() {
+        match lhs[i].cmp(&rhs[j]) {
+            Ordering::Less => {
+                domain.push(lhs[i]);
+                i += 1;
+            }
+            Ordering::Greater => {
+                domain.push(rhs[j]);
+                j += 1;
+            }
+            Ordering::Equal => {
+                domain.push(lhs[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    domain.extend_from_slice(&lhs[i..]);
+    domain.extend_from_slice(&rhs[j..]);
+    domain
+}
+
+// Values of `lhs` that are not in `rhs`, both sorted and without duplicates.
+#[cfg(feature = "trail")]
+fn difference_sorted<T>(lhs: &[T], rhs: &[T]) -> Vec<T>
+where
+    T: Copy + Ord,
+{
+    let mut rhs = rhs.iter().peekable();
+    lhs.iter()
+        .filter(|v| {
+            while rhs.next_if(|w| w < v).is_some() {}
+            rhs.peek() != Some(v)
+        })
+        .copied()
+        .collect()
+}
+
+// Linear merge intersection of two sorted slices without duplicates.
+fn intersection_sorted<T>(lhs: &[T], rhs: &[T]) -> Vec<T>
+where
+    T: Copy + Ord,
+{
+    use std::cmp::Ordering;
+    let mut domain = Vec::with_capacity(lhs.len().min(rhs.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() && j < rhs.len() {
+        match lhs[i].cmp(&rhs[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                domain.push(lhs[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    domain
+}
+
+/// Prunes both variables to the values that have a support in the other one under `rel`
+/// (AC-3 revise step in both directions).
+/// Returns an error of type `VariableError::DomainWipeout` if one of the variables has no
+/// supported value left, otherwise returns the `IntVariableState` of both variables.
+///
+/// # Parameters
+/// * `a` - The first variable.
+/// * `b` - The second variable.
+/// * `rel` - The allowed tuples, `rel(a_val, b_val)` is true iff the pair is allowed.
+pub fn arc_consistent<T, F>(
+    a: &mut IntVarValues<T>,
+    b: &mut IntVarValues<T>,
+    rel: F,
+) -> Result<(IntVariableState, IntVariableState), VariableError>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+    F: Fn(&T, &T) -> bool,
+{
+    // A single pass on each side is enough: every value kept in `a` supports the values
+    // of `b` it relies on, so revising `b` can not remove the support of a value of `a`.
+    let state_a = a.retain_supported(b, &rel)?;
+    let state_b = b.retain_supported(a, |w, v| rel(v, w))?;
+    Ok((state_a, state_b))
+}
+
+/// Returns the domain of `y = a * b`, that is the distinct products of a value of `a` with a
+/// value of `b`, or `None` if one of the domains is empty.
+/// The products that do not fit in `T` are left out, since `y` can not take them (so the
+/// result is also `None` if every product overflows).
+/// No size cap is enforced: every pair of values is enumerated, so the computation is in
+/// `O(n * m)` and the result holds up to `n * m` values. It is only meant for factors whose
+/// sizes multiply to a few thousand values at most.
+///
+/// # Parameters
+/// * `a` - The first factor.
+/// * `b` - The second factor.
+pub fn product_domain<T>(a: &IntVarValues<T>, b: &IntVarValues<T>) -> Option<IntVarValues<T>>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd + CheckedMul,
+{
+    let products = a
+        .domain
+        .iter()
+        .flat_map(|v| b.domain.iter().filter_map(move |w| v.checked_mul(w)));
+    IntVarValues::new_from_values(products)
+}
+
+/// Returns the domain of `z = a + b`, that is the distinct sums of a value of `a` with a value
+/// of `b`, or `None` if one of the domains is empty or if the result holds more than `cap`
+/// values. The sums of the runs of consecutive values of `a` and `b` are merged in ascending
+/// order with a heap, so that the computation is linear in the size of the result when the
+/// domains are intervals. The sums have to fit in `T`.
+///
+/// # Parameters
+/// * `a` - The first term.
+/// * `b` - The second term.
+/// * `cap` - The maximal number of values of the result.
+pub fn minkowski_sum<T>(
+    a: &IntVarValues<T>,
+    b: &IntVarValues<T>,
+    cap: usize,
+) -> Option<IntVarValues<T>>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd + std::ops::Add<Output = T> + One,
+{
+    let bounds = |run: &[T]| (run[0], run[run.len() - 1]);
+    let a_runs: Vec<_> = a.runs().map(bounds).collect();
+    let b_runs: Vec<_> = b.runs().map(bounds).collect();
+    let first = b_runs.first()?.0;
+    let mut heap: BinaryHeap<_> = a_runs
+        .iter()
+        .enumerate()
+        .map(|(i, &(min, _))| Reverse((min + first, i, 0)))
+        .collect();
+    let mut domain: Vec<T> = vec![];
+    while let Some(Reverse((min, i, j))) = heap.pop() {
+        if let Some(&(next, _)) = b_runs.get(j + 1) {
+            heap.push(Reverse((a_runs[i].0 + next, i, j + 1)));
+        }
+        let max = a_runs[i].1 + b_runs[j].1;
+        let mut v = match domain.last() {
+            Some(&last) if last >= max => continue,
+            Some(&last) if last >= min => last + T::one(),
+            _ => min,
+        };
+        loop {
+            if domain.len() == cap {
+                return None;
+            }
+            domain.push(v);
+            if v == max {
+                break;
+            }
+            v = v + T::one();
+        }
+    }
+    if domain.is_empty() {
+        None
+    } else {
+        Some(IntVarValues::from_sorted_vec(domain))
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    /*pub fn try_new<U>(min: U, max: U) -> Option<IntVarValues<U>>
+        where
+            U: Copy + Clone + Eq + PartialEq + Ord + PartialOrd + std::ops::Add<Output = U> + One,
+        {
+            if min > max {
+                None
+            } else {
+                let one = U::one();
+                let mut val = min;
+                let mut domain = vec![];
+                while val < max + one {
+                    domain.push(val);
+                    val = val + one;
+                }
+                Some(IntVarValues::from_sorted_vec(domain))
+            }
+        }
+    */
+    fn invalidate(&mut self) {
+        self.domain.clear();
+    }
+
+    fn domain_change<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        prev_min: T,
+        prev_max: T,
+        prev_size: usize,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        if self.domain.is_empty() {
+            self.invalidate();
+            observer.push_error(self.id, VariableError::DomainWipeout)
+        } else if self.size() == prev_size {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_min() != prev_min || *self.unchecked_max() != prev_max {
+            observer.push_change(self.id, IntVariableState::BoundsChange)
+        } else {
+            observer.push_change(self.id, IntVariableState::ValuesChange)
+        }
+    }
+}
+
+impl<T> IterableDomain<T> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a T> + 'a> {
+        Box::new(self.domain.iter())
+    }
+}
+
+impl<T> FromRangeDomain<T> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd + std::ops::Add<Output = T> + One,
+{
+    fn new_from_range(min: T, max: T) -> Option<IntVarValues<T>> {
+        if min > max {
+            None
+        } else {
+            let one = T::one();
+            let mut val = min;
+            let mut domain = vec![];
+            while val < max + one {
+                domain.push(val);
+                val = val + one;
+            }
+            Some(IntVarValues::from_sorted_vec(domain))
+        }
+    }
+}
+
+impl<T> FromValuesDomain<T> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    /// The values are sorted and consecutive duplicates are removed, which removes all the
+    /// duplicates as long as `Ord` is consistent with `Eq` (`a.cmp(&b) == Equal` iff `a == b`).
+    fn new_from_values<Values>(values: Values) -> Option<IntVarValues<T>>
+    where
+        Values: IntoIterator<Item = T>,
+    {
+        let mut domain = values.into_iter().collect::<Vec<_>>();
+        domain.sort();
+        domain.dedup();
+        if domain.is_empty() {
+            None
+        } else {
+            Some(IntVarValues::from_sorted_vec(domain))
+        }
+    }
+}
+
+/// Builds a variable from the half-open range `start..end`, that is the values
+/// `start, start + 1, ..., end - 1`. Unlike `new_from_range`, `end` is excluded, and the
+/// variable is empty (wiped out) if `start >= end`.
+impl<T> From<std::ops::Range<T>> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd + std::ops::Add<Output = T> + One,
+{
+    fn from(range: std::ops::Range<T>) -> IntVarValues<T> {
+        let mut domain = vec![];
+        let mut val = range.start;
+        while val < range.end {
+            domain.push(val);
+            val = val + T::one();
+        }
+        IntVarValues::from_sorted_vec(domain)
+    }
+}
+
+impl<T> AssignableDomain<T, IntVariableState> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn set_value(&mut self, value: T) -> Result<IntVariableState, VariableError> {
+        match self.value() {
+            Some(var_value) if *var_value == value => Ok(IntVariableState::NoChange),
+            _ if *self.unchecked_min() > value || *self.unchecked_max() < value => {
+                //self.invalidate();
+                Err(VariableError::DomainWipeout)
+            }
+            _ => {
+                let found_value = self.domain.binary_search(&value);
+                match found_value {
+                    Ok(_) => {
+                        let state = assignment_state(&self.domain, value);
+                        self.shrink_to(vec![value]);
+                        Ok(state)
+                    }
+                    _ => {
+                        self.invalidate();
+                        Err(VariableError::DomainWipeout)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> AssignableDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn set_value<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        value: T,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        if *self.unchecked_min() > value || *self.unchecked_max() < value {
+            //self.invalidate();
+            return observer.push_error(self.id, VariableError::DomainWipeout);
+        }
+        let var_value = self.value();
+        match var_value {
+            Some(var_value) if *var_value == value => Ok(IntVariableState::NoChange),
+            _ => {
+                let found_value = self.domain.binary_search(&value);
+                match found_value {
+                    Ok(_) => {
+                        let state = assignment_state(&self.domain, value);
+                        self.domain = vec![value];
+                        observer.push_change(self.id, state)
+                    }
+                    _ => {
+                        self.invalidate();
+                        observer.push_error(self.id, VariableError::DomainWipeout)
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T> Variable<T> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn is_affected(&self) -> bool {
+        self.domain.len() == 1
+    }
+
+    fn value(&self) -> Option<&T> {
+        if self.min() == self.max() {
+            self.min()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> Variable<T> for CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn is_affected(&self) -> bool {
+        self.domain.len() == 1
+    }
+
+    fn value(&self) -> Option<&T> {
+        if self.min() == self.max() {
+            self.min()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> CruspVariable<T> for CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn id(&self) -> VariableId {
+        self.id
+    }
+}
+
+impl<T> FiniteDomain<T> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn size(&self) -> usize {
+        self.domain.len()
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> FiniteDomain<T> for CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn size(&self) -> usize {
+        self.domain.len()
+    }
+}
+
+impl<T> OrderedDomain<T, IntVariableState> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn min(&self) -> Option<&T> {
+        self.domain.first()
+    }
+    fn max(&self) -> Option<&T> {
+        self.domain.last()
+    }
+
+    fn strict_upperbound(&mut self, ub: &T) -> Result<IntVariableState, VariableError> {
+        if *self.unchecked_max() < *ub {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_min() >= *ub {
+            Err(VariableError::BoundWipeout { lower: false })
+        } else {
+            let index = self.domain.iter().rposition(|&val| val < *ub).unwrap();
+            self.remove_range(index + 1..self.domain.len());
+            Ok(IntVariableState::BoundsChange)
+        }
+    }
+
+    fn weak_upperbound(&mut self, ub: &T) -> Result<IntVariableState, VariableError> {
+        if *self.unchecked_max() <= *ub {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_min() > *ub {
+            Err(VariableError::BoundWipeout { lower: false })
+        } else {
+            let index = self.domain.iter().rposition(|&val| val <= *ub).unwrap();
+            self.remove_range(index + 1..self.domain.len());
+            Ok(IntVariableState::BoundsChange)
+        }
+    }
+
+    fn strict_lowerbound(&mut self, lb: &T) -> Result<IntVariableState, VariableError> {
+        if *self.unchecked_min() > *lb {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_max() <= *lb {
+            Err(VariableError::BoundWipeout { lower: true })
+        } else {
+            let index = self.domain.iter().position(|&val| val > *lb).unwrap();
+            self.remove_range(0..index);
+            Ok(IntVariableState::BoundsChange)
+        }
+    }
+
+    fn weak_lowerbound(&mut self, lb: &T) -> Result<IntVariableState, VariableError> {
+        if *self.unchecked_min() >= *lb {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_max() < *lb {
+            Err(VariableError::BoundWipeout { lower: true })
+        } else {
+            let index = self.domain.iter().position(|&val| val >= *lb).unwrap();
+            self.remove_range(0..index);
+            Ok(IntVariableState::BoundsChange)
+        }
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> OrderedDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn min(&self) -> Option<&T> {
+        self.domain.first()
+    }
+    fn max(&self) -> Option<&T> {
+        self.domain.last()
+    }
+
+    fn strict_upperbound<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        ub: &T,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        if *self.unchecked_max() < *ub {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_min() >= *ub {
+            observer.push_error(self.id, VariableError::BoundWipeout { lower: false })
+        } else {
+            let index = self.domain.iter().rposition(|&val| val < *ub).unwrap();
+            self.domain.truncate(index + 1);
+            observer.push_change(self.id, IntVariableState::BoundsChange)
+        }
+    }
+
+    fn weak_upperbound<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        ub: &T,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        if *self.unchecked_max() <= *ub {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_min() > *ub {
+            observer.push_error(self.id, VariableError::BoundWipeout { lower: false })
+        } else {
+            let index = self.domain.iter().rposition(|&val| val <= *ub).unwrap();
+            self.domain.truncate(index + 1);
+            observer.push_change(self.id, IntVariableState::BoundsChange)
+        }
+    }
+
+    fn strict_lowerbound<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        lb: &T,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        if *self.unchecked_min() > *lb {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_max() <= *lb {
+            observer.push_error(self.id, VariableError::BoundWipeout { lower: true })
+        } else {
+            let index = self.domain.iter().position(|&val| val > *lb).unwrap();
+            self.domain.drain(0..index);
+            observer.push_change(self.id, IntVariableState::BoundsChange)
+        }
+    }
+
+    fn weak_lowerbound<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        lb: &T,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        if *self.unchecked_min() >= *lb {
+            Ok(IntVariableState::NoChange)
+        } else if *self.unchecked_max() < *lb {
+            observer.push_error(self.id, VariableError::BoundWipeout { lower: true })
+        } else {
+            let index = self.domain.iter().position(|&val| val >= *lb).unwrap();
+            self.domain.drain(0..index);
+            observer.push_change(self.id, IntVariableState::BoundsChange)
+        }
+    }
+}
+
+impl<T> EqualDomain<T, IntVariableState> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    // Distinction between ValuesChange and BoundsChange
+    fn equal(
+        &mut self,
+        value: &mut Self,
+    ) -> Result<(IntVariableState, IntVariableState), VariableError> {
+        if self.domain == value.domain {
+            return Ok((IntVariableState::NoChange, IntVariableState::NoChange));
+        }
+        use std::collections::BTreeSet;
+        let s1: BTreeSet<_> = self.iter().copied().collect();
+        let s2: BTreeSet<_> = value.iter().copied().collect();
+        let domain: Vec<_> = s1.intersection(&s2).copied().collect();
+
+        if domain.is_empty() {
+            self.invalidate();
+            value.invalidate();
+            return Err(VariableError::DomainWipeout);
+        }
+        let (ok_self, ok_value) = {
+            let check_change = |var: &mut IntVarValues<T>| {
+                if var.size() == domain.len() {
+                    IntVariableState::NoChange
+                } else if *var.unchecked_min() != unwrap_first!(domain)
+                    || *var.unchecked_max() != unwrap_last!(domain)
+                {
+                    IntVariableState::BoundsChange
+                } else {
+                    IntVariableState::ValuesChange
+                }
+            };
+            (check_change(self), check_change(value))
+        };
+
+        self.shrink_to(domain.clone());
+        value.shrink_to(domain);
+        Ok((ok_self, ok_value))
+    }
+    fn not_equal(
+        &mut self,
+        value: &mut IntVarValues<T>,
+    ) -> Result<(IntVariableState, IntVariableState), VariableError> {
+        match self.value() {
+            Some(val) => {
+                let ok_value = value.remove_value(*val)?;
+                Ok((IntVariableState::NoChange, ok_value))
+            }
+            _ => match value.value() {
+                Some(val) => {
+                    let ok_self = self.remove_value(*val)?;
+                    Ok((ok_self, IntVariableState::NoChange))
+                }
+                _ => Ok((IntVariableState::NoChange, IntVariableState::NoChange)),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> EqualDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    // Distinction between ValuesChange and BoundsChange
+    fn equal<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        value: &mut Self,
+    ) -> Result<(IntVariableState, IntVariableState), VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        if self.domain == value.domain {
+            return Ok((IntVariableState::NoChange, IntVariableState::NoChange));
+        }
+        use std::collections::BTreeSet;
+        let s1: BTreeSet<_> = self.domain.iter().copied().collect();
+        let s2: BTreeSet<_> = value.domain.iter().copied().collect();
+        let domain: Vec<_> = s1.intersection(&s2).copied().collect();
+
+        if domain.is_empty() {
+            self.invalidate();
+            value.invalidate();
+            let _err = observer.push_error(self.id, VariableError::DomainWipeout);
+            let _err = observer.push_error(value.id, VariableError::DomainWipeout);
+            return Err(VariableError::DomainWipeout);
+        }
+        let (ok_self, ok_value) = {
+            let check_change = |var: &mut CruspIntVarValues<T>| {
+                if var.size() == domain.len() {
+                    IntVariableState::NoChange
+                } else if *var.unchecked_min() != unwrap_first!(domain)
+                    || *var.unchecked_max() != unwrap_last!(domain)
+                {
+                    IntVariableState::BoundsChange
+                } else {
+                    IntVariableState::ValuesChange
+                }
+            };
+            (check_change(self), check_change(value))
+        };
+
+        if ok_self != IntVariableState::NoChange {
+            let _change = observer.push_change(self.id, ok_self);
+        }
+
+        if ok_value != IntVariableState::NoChange {
+            let _change = observer.push_change(value.id, ok_value);
+        }
+        self.domain = domain.clone();
+        value.domain = domain;
+        Ok((ok_self, ok_value))
+    }
+    fn not_equal<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        value: &mut CruspIntVarValues<T>,
+    ) -> Result<(IntVariableState, IntVariableState), VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        match self.value() {
+            Some(val) => {
+                let ok_value = value.remove_value(observer, *val)?;
+                Ok((IntVariableState::NoChange, ok_value))
+            }
+            _ => match value.value() {
+                Some(val) => {
+                    let ok_self = self.remove_value(observer, *val)?;
+                    let _change = observer.push_change(self.id, ok_self);
+                    Ok((ok_self, IntVariableState::NoChange))
+                }
+                _ => Ok((IntVariableState::NoChange, IntVariableState::NoChange)),
+            },
+        }
+    }
+}
+
+impl<T> PrunableDomain<T, IntVariableState> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn in_values<Values>(&mut self, values: Values) -> Result<IntVariableState, VariableError>
+    where
+        Values: IntoIterator<Item = T>,
+    {
+        let values: Vec<_> = values.into_iter().collect();
+        let mut values: Vec<_> = values.into_iter().collect();
+        values.sort();
+        self.in_sorted_values(values)
+    }
+
+    // check change function (equality, bounds, values, nochange...)
+    fn remove_value(&mut self, value: T) -> Result<IntVariableState, VariableError> {
+        if *self.unchecked_min() > value || *self.unchecked_max() < value {
+            return Ok(IntVariableState::NoChange);
+        }
+        let (min, max) = (self.min().copied(), self.max().copied());
+        let found_value = self.domain.binary_search(&value);
+        match found_value {
+            Ok(index) => {
+                self.remove_range(index..index + 1);
+                if self.size() == 0 {
+                    Err(VariableError::DomainWipeout)
+                } else if self.min().copied() != min || self.max().copied() != max {
+                    Ok(IntVariableState::BoundsChange)
+                } else {
+                    Ok(IntVariableState::ValuesChange)
+                }
+            }
+            _ => Ok(IntVariableState::NoChange),
+        }
+    }
+
+    fn remove_if<Predicate>(
+        &mut self,
+        mut pred: Predicate,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Predicate: FnMut(&T) -> bool,
+    {
+        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
+        self.retain_domain(|v| !pred(v));
+        self.domain_change(min, max, size)
+    }
+
+    fn retains_if<Predicate>(
+        &mut self,
+        mut pred: Predicate,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Predicate: FnMut(&T) -> bool,
+    {
+        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
+        self.retain_domain(&mut pred);
+        self.domain_change(min, max, size)
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> PrunableDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    fn in_values<Observer, Values>(
+        &mut self,
+        observer: &mut Observer,
+        values: Values,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+        Values: IntoIterator<Item = T>,
+    {
+        let values: Vec<_> = values.into_iter().collect();
+        let mut values: Vec<_> = values.into_iter().collect();
+        values.sort();
+        self.in_sorted_values(observer, values)
+    }
+
+    // check change function (equality, bounds, values, nochange...)
+    fn remove_value<Observer>(
+        &mut self,
+        observer: &mut Observer,
+        value: T,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+    {
+        if *self.unchecked_min() > value || *self.unchecked_max() < value {
+            return Ok(IntVariableState::NoChange);
+        }
+        let (min, max) = (self.min().copied(), self.max().copied());
+        let found_value = self.domain.binary_search(&value);
+        match found_value {
+            Ok(index) => {
+                self.domain.remove(index);
+                if self.size() == 0 {
+                    observer.push_error(self.id, VariableError::DomainWipeout)
+                } else if self.min().copied() != min || self.max().copied() != max {
+                    observer.push_change(self.id, IntVariableState::BoundsChange)
+                } else {
+                    observer.push_change(self.id, IntVariableState::ValuesChange)
+                }
+            }
+            _ => Ok(IntVariableState::NoChange),
+        }
+    }
+
+    fn remove_if<Observer, Predicate>(
+        &mut self,
+        observer: &mut Observer,
+        mut pred: Predicate,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+        Predicate: FnMut(&T) -> bool,
+    {
+        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
+        self.domain.retain(|v| !pred(v));
+        self.domain_change(observer, min, max, size)
+    }
+
+    fn retains_if<Observer, Predicate>(
+        &mut self,
+        observer: &mut Observer,
+        mut pred: Predicate,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+        Predicate: FnMut(&T) -> bool,
+    {
+        let (min, max, size) = (*self.unchecked_min(), *self.unchecked_max(), self.size());
+        self.domain.retain(|v| pred(v));
+        self.domain_change(observer, min, max, size)
+    }
+}
+
+impl<T> OrderedPrunableDomain<T, IntVariableState> for IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    // Change to non-naive implementation
+    fn in_sorted_values<Values>(
+        &mut self,
+        values: Values,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Values: IntoIterator<Item = T>,
+    {
+        use std::collections::BTreeSet;
+        let s1: BTreeSet<_> = self.iter().copied().collect();
+        let s2: BTreeSet<_> = values.into_iter().collect();
+        let domain: Vec<_> = s1.intersection(&s2).copied().collect();
+
+        if domain.is_empty() {
+            self.invalidate();
+            return Err(VariableError::DomainWipeout);
+        }
+        let ok_self = {
+            let check_change = |var: &mut IntVarValues<T>| {
+                if var.size() == domain.len() {
+                    IntVariableState::NoChange
+                } else if *var.unchecked_min() != unwrap_first!(domain)
+                    || *var.unchecked_max() != unwrap_last!(domain)
+                {
+                    IntVariableState::BoundsChange
+                } else {
+                    IntVariableState::ValuesChange
+                }
+            };
+            check_change(self)
+        };
+        self.shrink_to(domain);
+        Ok(ok_self)
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<T> OrderedPrunableDomainObserver<T, IntVariableState> for CruspIntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    // Change to non-naive implementation
+    fn in_sorted_values<Observer, Values>(
+        &mut self,
+        observer: &mut Observer,
+        values: Values,
+    ) -> Result<IntVariableState, VariableError>
+    where
+        Observer: VariableObserver<IntVariableState>,
+        Values: IntoIterator<Item = T>,
+    {
+        use std::collections::BTreeSet;
+        let s1: BTreeSet<_> = self.domain.iter().copied().collect();
+        let s2: BTreeSet<_> = values.into_iter().collect();
+        let domain: Vec<_> = s1.intersection(&s2).copied().collect();
+
+        if domain.is_empty() {
+            self.invalidate();
+            return observer.push_error(self.id, VariableError::DomainWipeout);
         }
         let ok_self = {
             let mut check_change = |var: &mut CruspIntVarValues<T>, vid: VariableId| {
@@ -844,10 +2465,1533 @@ where
                     observer.push_change(vid, IntVariableState::ValuesChange)
                 }
             };
-            let vid = self.id;
-            check_change(self, vid)
-        };
-        self.domain = domain;
-        ok_self
+            let vid = self.id;
+            check_change(self, vid)
+        };
+        self.domain = domain;
+        ok_self
+    }
+}
+
+/// Returns the intersection of both domains, which is empty (wiped out) if they have no common
+/// value.
+impl<'a, T> std::ops::BitAnd for &'a IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    type Output = IntVarValues<T>;
+
+    fn bitand(self, other: &'a IntVarValues<T>) -> IntVarValues<T> {
+        IntVarValues::from_sorted_vec(intersection_sorted(&self.domain, &other.domain))
+    }
+}
+
+/// Returns the union of both domains.
+impl<'a, T> std::ops::BitOr for &'a IntVarValues<T>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    type Output = IntVarValues<T>;
+
+    fn bitor(self, other: &'a IntVarValues<T>) -> IntVarValues<T> {
+        IntVarValues::from_sorted_vec(union_sorted(&self.domain, &other.domain))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArrayOfVars;
+
+    #[test]
+    fn test_as_slice() {
+        let var = IntVarValues::new_from_values(vec![5, 1, 3, 1, 9]).unwrap();
+        assert_eq!(var.as_slice(), &[1, 3, 5, 9]);
+        assert_eq!(var.as_slice().binary_search(&5), Ok(2));
+        assert_eq!(var.as_slice().binary_search(&4), Err(2));
+    }
+
+    #[test]
+    fn test_retain_supported() {
+        let mut x = IntVarValues::new_from_values(vec![1, 2, 3, 4, 5]).unwrap();
+        let y = IntVarValues::new_from_values(vec![5, 7, 9]).unwrap();
+        assert_eq!(
+            x.retain_supported(&y, |a, b| a + b == 10),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(x.as_slice(), &[1, 3, 5]);
+        assert_eq!(
+            x.retain_supported(&y, |a, b| a + b == 10),
+            Ok(IntVariableState::NoChange)
+        );
+        let y = IntVarValues::new_from_values(vec![7]).unwrap();
+        assert_eq!(
+            x.retain_supported(&y, |a, b| a + b == 10),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(x.as_slice(), &[3]);
+        let y = IntVarValues::new_from_values(vec![1]).unwrap();
+        assert_eq!(
+            x.retain_supported(&y, |a, b| a + b == 10),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_arc_consistent() {
+        let mut a = IntVarValues::new_from_values(vec![1, 2]).unwrap();
+        let mut b = IntVarValues::new_from_values(vec![1]).unwrap();
+        assert_eq!(
+            arc_consistent(&mut a, &mut b, |x, y| x != y),
+            Ok((IntVariableState::BoundsChange, IntVariableState::NoChange))
+        );
+        assert_eq!(a.as_slice(), &[2]);
+        assert_eq!(b.as_slice(), &[1]);
+
+        let mut a = IntVarValues::new_from_values(1..=5).unwrap();
+        let mut b = IntVarValues::new_from_values(1..=3).unwrap();
+        assert_eq!(
+            arc_consistent(&mut a, &mut b, |x, y| x < y),
+            Ok((
+                IntVariableState::BoundsChange,
+                IntVariableState::BoundsChange
+            ))
+        );
+        assert_eq!(a.as_slice(), &[1, 2]);
+        assert_eq!(b.as_slice(), &[2, 3]);
+
+        let mut a = IntVarValues::new_from_values(vec![4, 5]).unwrap();
+        let mut b = IntVarValues::new_from_values(vec![1, 2]).unwrap();
+        assert_eq!(
+            arc_consistent(&mut a, &mut b, |x, y| x < y),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_equal_same_domains() {
+        let mut x = IntVarValues::new_from_values(vec![1, 3, 5]).unwrap();
+        let mut y = x.clone();
+        assert_eq!(
+            x.equal(&mut y),
+            Ok((IntVariableState::NoChange, IntVariableState::NoChange))
+        );
+        assert_eq!(x.as_slice(), &[1, 3, 5]);
+        assert_eq!(y.as_slice(), &[1, 3, 5]);
+    }
+
+    #[test]
+    fn test_smallest_largest_k() {
+        let var = IntVarValues::new_from_values(vec![4, 1, 9, 7]).unwrap();
+        assert_eq!(var.smallest_k(2), &[1, 4]);
+        assert_eq!(var.largest_k(2), &[7, 9]);
+        assert_eq!(var.smallest_k(4), &[1, 4, 7, 9]);
+        assert_eq!(var.largest_k(4), &[1, 4, 7, 9]);
+        assert_eq!(var.smallest_k(10), &[1, 4, 7, 9]);
+        assert_eq!(var.largest_k(10), &[1, 4, 7, 9]);
+        assert!(var.smallest_k(0).is_empty());
+        assert!(var.largest_k(0).is_empty());
+    }
+
+    #[test]
+    fn test_bisect() {
+        let var = IntVarValues::new_from_values(vec![1, 2, 5, 8]).unwrap();
+        let (lower, upper) = var.bisect().unwrap();
+        assert_eq!(lower.as_slice(), &[1, 2]);
+        assert_eq!(upper.as_slice(), &[5, 8]);
+        assert_eq!(var.as_slice(), &[1, 2, 5, 8]);
+
+        let var = IntVarValues::new_from_values(vec![1, 2, 5, 8, 9]).unwrap();
+        let (lower, upper) = var.bisect().unwrap();
+        let mut halves = lower.as_slice().to_vec();
+        halves.extend_from_slice(upper.as_slice());
+        assert_eq!(halves, var.as_slice());
+        assert!(lower.size() > 0 && upper.size() > 0);
+        assert!(lower.unchecked_max() < upper.unchecked_min());
+
+        let var = IntVarValues::new_from_values(vec![3]).unwrap();
+        assert_eq!(var.bisect(), None);
+    }
+
+    #[test]
+    fn test_divided_by() {
+        let var = IntVarValues::new_from_values(vec![4, 5, 6, 7]).unwrap();
+        assert_eq!(var.divided_by(2).unwrap().as_slice(), &[2, 3]);
+        assert_eq!(var.divided_by(-2).unwrap().as_slice(), &[-3, -2]);
+        let var = IntVarValues::new_from_values(vec![-5, -1, 0, 1, 5]).unwrap();
+        assert_eq!(var.divided_by(3).unwrap().as_slice(), &[-1, 0, 1]);
+        assert_eq!(var.divided_by(0), Err(VariableError::InvalidArgument));
+        let var = IntVarValues::new_from_values(vec![i32::MIN, 0]).unwrap();
+        assert_eq!(var.divided_by(-1), Err(VariableError::Overflow));
+        assert_eq!(var.divided_by(1).unwrap().as_slice(), &[i32::MIN, 0]);
+    }
+
+    #[test]
+    fn test_modulo() {
+        let var = IntVarValues::new_from_values(0..=9).unwrap();
+        assert_eq!(var.modulo(3).unwrap().as_slice(), &[0, 1, 2]);
+        assert_eq!(var.modulo(-3).unwrap().as_slice(), &[0, 1, 2]);
+        let var = IntVarValues::new_from_values(vec![-4, -3, 5]).unwrap();
+        assert_eq!(var.modulo(3).unwrap().as_slice(), &[-1, 0, 2]);
+        assert_eq!(var.modulo(0), Err(VariableError::InvalidArgument));
+        let var = IntVarValues::new_from_values(vec![i32::MIN, 7]).unwrap();
+        assert_eq!(var.modulo(-1).unwrap().as_slice(), &[0]);
+    }
+
+    #[test]
+    fn test_iter_tagged() {
+        let var = IntVarValues::new_from_values(vec![1, 3, 5, 7]).unwrap();
+        let tagged: Vec<_> = var.iter_tagged().collect();
+        assert_eq!(
+            tagged,
+            vec![(&1, true), (&3, false), (&5, false), (&7, true)]
+        );
+        assert_eq!(var.iter_tagged().filter(|&(_, bound)| bound).count(), 2);
+        let var = IntVarValues::new_from_values(vec![4]).unwrap();
+        assert_eq!(var.iter_tagged().collect::<Vec<_>>(), vec![(&4, true)]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut var = IntVarValues::<i32>::empty();
+        assert!(var.is_wiped());
+        assert_eq!(var.size(), 0);
+        assert_eq!(var.value(), None);
+        var.union(&IntVarValues::new_from_values(vec![5, 1]).unwrap());
+        assert!(!var.is_wiped());
+        var.union(&IntVarValues::new_from_values(vec![3, 5, 7]).unwrap());
+        assert_eq!(var.as_slice(), &[1, 3, 5, 7]);
+    }
+
+    #[test]
+    fn test_retains_if_count() {
+        let mut var = IntVarValues::new_from_values(1..=10).unwrap();
+        let (state, count) = var.retains_if_count(|v| v % 3 == 0).unwrap();
+        assert_eq!(state, IntVariableState::BoundsChange);
+        assert_eq!(count, 3);
+        assert_eq!(count, var.size());
+        assert_eq!(
+            var.retains_if_count(|v| *v > 10),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let x = IntVarValues::new_from_values(vec![1, 2, 3, 5, 8]).unwrap();
+        let y = IntVarValues::new_from_values(vec![2, 3, 4, 9]).unwrap();
+        assert_eq!(x.symmetric_difference(&y).as_slice(), &[1, 4, 5, 8, 9]);
+        assert_eq!(y.symmetric_difference(&x).as_slice(), &[1, 4, 5, 8, 9]);
+        assert!(x.symmetric_difference(&x).is_wiped());
+    }
+
+    #[test]
+    fn test_product_domain() {
+        let a = IntVarValues::new_from_values(vec![2, 3]).unwrap();
+        let b = IntVarValues::new_from_values(vec![4, 5]).unwrap();
+        assert_eq!(product_domain(&a, &b).unwrap().as_slice(), &[8, 10, 12, 15]);
+        let b = IntVarValues::new_from_values(vec![-1, 0, 2]).unwrap();
+        assert_eq!(
+            product_domain(&a, &b).unwrap().as_slice(),
+            &[-3, -2, 0, 4, 6]
+        );
+        assert_eq!(product_domain(&a, &IntVarValues::empty()), None);
+        let b = IntVarValues::new_from_values(vec![1, i32::MAX]).unwrap();
+        assert_eq!(product_domain(&a, &b).unwrap().as_slice(), &[2, 3]);
+        let a = IntVarValues::new_from_values(vec![i32::MIN]).unwrap();
+        let b = IntVarValues::new_from_values(vec![-1]).unwrap();
+        assert_eq!(product_domain(&a, &b), None);
+    }
+
+    #[test]
+    fn test_retain_factor() {
+        let mut a = IntVarValues::new_from_values(1..=6).unwrap();
+        let b = IntVarValues::new_from_values(vec![2, 3]).unwrap();
+        let product = IntVarValues::new_from_values(vec![6, 8, 9]).unwrap();
+        assert_eq!(
+            a.retain_factor(&b, &product),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(a.as_slice(), &[2, 3, 4]);
+
+        let mut a = IntVarValues::new_from_values(vec![0, 1, 5]).unwrap();
+        let b = IntVarValues::new_from_values(vec![7]).unwrap();
+        let product = IntVarValues::new_from_values(vec![0, 35]).unwrap();
+        assert_eq!(
+            a.retain_factor(&b, &product),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(a.as_slice(), &[0, 5]);
+        let product = IntVarValues::new_from_values(vec![0]).unwrap();
+        assert_eq!(
+            a.retain_factor(&IntVarValues::empty(), &product),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(a.as_slice(), &[0]);
+
+        let mut a = IntVarValues::new_from_values(vec![1, 2]).unwrap();
+        let b = IntVarValues::new_from_values(vec![i32::MAX]).unwrap();
+        let product = IntVarValues::new_from_values(vec![-2, i32::MAX]).unwrap();
+        assert_eq!(
+            a.retain_factor(&b, &product),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(a.as_slice(), &[1]);
+    }
+
+    #[test]
+    fn test_insert_value() {
+        let mut var = IntVarValues::new_from_values(vec![1, 5, 9]).unwrap();
+        assert!(var.insert_value(6));
+        assert!(var.insert_value(0));
+        assert!(var.insert_value(10));
+        assert!(!var.insert_value(5));
+        assert_eq!(var.as_slice(), &[0, 1, 5, 6, 9, 10]);
+        let mut var = IntVarValues::empty();
+        assert!(var.insert_value(3));
+        assert_eq!(var.as_slice(), &[3]);
+    }
+
+    #[test]
+    fn test_definitely_possibly_equal() {
+        let x = IntVarValues::new_from_values(vec![4]).unwrap();
+        let y = IntVarValues::new_from_values(vec![4]).unwrap();
+        let z = IntVarValues::new_from_values(vec![5]).unwrap();
+        assert!(x.definitely_equal(&y));
+        assert!(x.possibly_equal(&y));
+        assert!(!x.definitely_equal(&z));
+        assert!(!x.possibly_equal(&z));
+
+        let x = IntVarValues::new_from_values(vec![1, 5]).unwrap();
+        let y = IntVarValues::new_from_values(vec![3, 8]).unwrap();
+        let z = IntVarValues::new_from_values(vec![6, 8]).unwrap();
+        assert!(!x.definitely_equal(&x.clone()));
+        assert!(x.possibly_equal(&y));
+        assert!(y.possibly_equal(&x));
+        assert!(!x.possibly_equal(&z));
+    }
+
+    #[test]
+    fn test_is_interval() {
+        assert!(IntVarValues::new_from_values(3..=7).unwrap().is_interval());
+        assert!(!IntVarValues::new_from_values(vec![3, 4, 6])
+            .unwrap()
+            .is_interval());
+        assert!(IntVarValues::new_from_values(vec![5])
+            .unwrap()
+            .is_interval());
+        assert!(IntVarValues::new_from_values(i8::MIN..=i8::MAX)
+            .unwrap()
+            .is_interval());
+        assert!(!IntVarValues::new_from_values(vec![i8::MIN, i8::MAX])
+            .unwrap()
+            .is_interval());
+        assert!(!IntVarValues::<i32>::empty().is_interval());
+    }
+
+    #[test]
+    fn test_ensure_size_at_least() {
+        let mut var = IntVarValues::new_from_values(vec![1, 4, 6]).unwrap();
+        assert_eq!(var.ensure_size_at_least(3), Ok(()));
+        assert_eq!(var.ensure_size_at_least(2), Ok(()));
+        assert_eq!(
+            var.ensure_size_at_least(4),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_retain_in_iter() {
+        let mut var = IntVarValues::new_from_values(1..=8).unwrap();
+        assert_eq!(
+            var.retain_in_iter(vec![0, 2, 3, 3, 7, 8, 12].into_iter()),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[2, 3, 7, 8]);
+        let other = IntVarValues::new_from_values(vec![2, 5, 8, 9]).unwrap();
+        assert_eq!(
+            var.retain_in_iter(other.iter().copied()),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(var.as_slice(), &[2, 8]);
+        assert_eq!(
+            var.retain_in_iter(other.iter().copied()),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(
+            var.retain_in_iter(vec![1, 3].into_iter()),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn test_retain_in_iter_unsorted() {
+        let mut var = IntVarValues::new_from_values(1..=8).unwrap();
+        let _ = var.retain_in_iter(vec![5, 2].into_iter());
+    }
+
+    #[test]
+    fn test_assign_if_possible() {
+        let mut var = IntVarValues::new_from_values(vec![1, 3, 5]).unwrap();
+        assert!(!var.assign_if_possible(2));
+        assert_eq!(var.as_slice(), &[1, 3, 5]);
+        assert!(!var.assign_if_possible(7));
+        assert_eq!(var.as_slice(), &[1, 3, 5]);
+        assert!(var.assign_if_possible(3));
+        assert_eq!(var.value(), Some(&3));
+        assert!(var.assign_if_possible(3));
+        assert!(!var.assign_if_possible(5));
+        assert_eq!(var.value(), Some(&3));
+    }
+
+    #[test]
+    fn test_retain_by_mask() {
+        let mut mask = ArrayOfVars::new(5, BoolVar::new().unwrap()).unwrap();
+        mask.get_mut(1).unwrap().set_value(false).unwrap();
+        mask.get_mut(3).unwrap().set_value(true).unwrap();
+        mask.get_mut(4).unwrap().set_value(false).unwrap();
+        let mut var = IntVarValues::new_from_values(vec![-1, 1, 2, 3, 4, 7]).unwrap();
+        assert_eq!(
+            var.retain_by_mask(&mask),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[2, 3]);
+        let mut var = IntVarValues::new_from_values(vec![1, 4]).unwrap();
+        assert_eq!(var.retain_by_mask(&mask), Err(VariableError::DomainWipeout));
+    }
+
+    #[test]
+    fn test_new_from_values_dedup() {
+        let var = IntVarValues::new_from_values(vec![3, 1, 2, 1, 3, 2]).unwrap();
+        assert_eq!(var.as_slice(), &[1, 2, 3]);
+        let var = IntVarValues::new_from_values(vec![5, 5, 5]).unwrap();
+        assert_eq!(var.as_slice(), &[5]);
+    }
+
+    #[test]
+    fn test_bitand_bitor() {
+        let x = IntVarValues::new_from_values(vec![1, 3, 5, 7]).unwrap();
+        let y = IntVarValues::new_from_values(vec![2, 3, 4, 7, 9]).unwrap();
+        assert_eq!((&x & &y).as_slice(), &[3, 7]);
+        assert_eq!((&x | &y).as_slice(), &[1, 2, 3, 4, 5, 7, 9]);
+        let z = IntVarValues::new_from_values(vec![2, 4]).unwrap();
+        assert!((&x & &z).is_wiped());
+        assert_eq!(x.as_slice(), &[1, 3, 5, 7]);
+    }
+
+    #[test]
+    fn test_nearest() {
+        let var = IntVarValues::new_from_values(vec![2, 4, 9]).unwrap();
+        assert_eq!(var.nearest(-5), Some(2));
+        assert_eq!(var.nearest(12), Some(9));
+        assert_eq!(var.nearest(3), Some(2));
+        assert_eq!(var.nearest(4), Some(4));
+        assert_eq!(var.nearest(7), Some(9));
+        assert_eq!(IntVarValues::<u8>::empty().nearest(3), None);
+        let var = IntVarValues::new_from_values(vec![i32::MIN, i32::MAX]).unwrap();
+        assert_eq!(var.nearest(0), Some(i32::MAX));
+        assert_eq!(var.nearest(1), Some(i32::MAX));
+        assert_eq!(var.nearest(-1), Some(i32::MIN));
+    }
+
+    #[test]
+    fn test_floor_ceil_value() {
+        let var = IntVarValues::new_from_values(vec![2, 4, 9]).unwrap();
+        assert_eq!((var.floor_value(4), var.ceil_value(4)), (Some(4), Some(4)));
+        assert_eq!((var.floor_value(6), var.ceil_value(6)), (Some(4), Some(9)));
+        assert_eq!((var.floor_value(1), var.ceil_value(1)), (None, Some(2)));
+        assert_eq!((var.floor_value(10), var.ceil_value(10)), (Some(9), None));
+    }
+
+    #[test]
+    fn test_from_range_intersection() {
+        let var = IntVarValues::from_range_intersection(&[(2, 10), (5, 15)]).unwrap();
+        assert_eq!(var.as_slice(), &[5, 6, 7, 8, 9, 10]);
+        assert_eq!(
+            IntVarValues::from_range_intersection(&[(1, 3), (5, 8)]),
+            None
+        );
+        let var = IntVarValues::from_range_intersection(&[(-1, 1)]).unwrap();
+        assert_eq!(var.as_slice(), &[-1, 0, 1]);
+        assert_eq!(IntVarValues::<i32>::from_range_intersection(&[]), None);
+    }
+
+    #[test]
+    fn test_from_vec_checked() {
+        let var = IntVarValues::from_vec_checked(vec![1, 4, 6]).unwrap();
+        assert_eq!(var.as_slice(), &[1, 4, 6]);
+        assert_eq!(
+            IntVarValues::from_vec_checked(vec![1, 6, 4]),
+            Err(VariableError::InvalidArgument)
+        );
+        assert_eq!(
+            IntVarValues::from_vec_checked(vec![1, 4, 4]),
+            Err(VariableError::InvalidArgument)
+        );
+        assert_eq!(
+            IntVarValues::<i32>::from_vec_checked(vec![]),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_fragmentation() {
+        let var = IntVarValues::new_from_values(3..=7).unwrap();
+        assert_eq!(var.fragmentation(), 1.0);
+        let var = IntVarValues::new_from_values(vec![0, 1, 9]).unwrap();
+        assert_eq!(var.fragmentation(), 0.3);
+        assert_eq!(IntVarValues::<i32>::empty().fragmentation(), 0.0);
+    }
+
+    #[test]
+    fn test_remove_open_interval() {
+        let mut var = IntVarValues::new_from_values(1..=9).unwrap();
+        assert_eq!(
+            var.remove_open_interval(None, Some(3)),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(
+            var.remove_open_interval(Some(7), None),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[4, 5, 6]);
+        assert_eq!(
+            var.remove_open_interval(Some(5), Some(5)),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(var.as_slice(), &[4, 6]);
+        assert_eq!(
+            var.remove_open_interval(Some(5), Some(2)),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(
+            var.remove_open_interval(None, None),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_domain_gcd() {
+        let var = IntVarValues::new_from_values(vec![4, 6, 8]).unwrap();
+        assert_eq!(var.domain_gcd(), Some(2));
+        let var = IntVarValues::new_from_values(vec![3, 5]).unwrap();
+        assert_eq!(var.domain_gcd(), Some(1));
+        let var = IntVarValues::new_from_values(vec![12]).unwrap();
+        assert_eq!(var.domain_gcd(), Some(12));
+        assert_eq!(IntVarValues::<i32>::empty().domain_gcd(), None);
+    }
+
+    #[test]
+    fn test_checked_offset() {
+        let mut var = IntVarValues::new_from_values(vec![1i8, 3, 5]).unwrap();
+        assert_eq!(var.checked_offset(2), Ok(IntVariableState::BoundsChange));
+        assert_eq!(var.as_slice(), &[3, 5, 7]);
+        assert_eq!(var.checked_offset(0), Ok(IntVariableState::NoChange));
+        assert_eq!(var.checked_offset(-4), Ok(IntVariableState::BoundsChange));
+        assert_eq!(var.as_slice(), &[-1, 1, 3]);
+        let mut var = IntVarValues::new_from_values(vec![100i8, 120, 126]).unwrap();
+        assert_eq!(var.checked_offset(2), Err(VariableError::Overflow));
+        assert_eq!(var.as_slice(), &[100, 120, 126]);
+    }
+
+    #[test]
+    fn test_chunks() {
+        let var = IntVarValues::new_from_values(1..=7).unwrap();
+        let chunks: Vec<_> = var.chunks(3).collect();
+        assert_eq!(chunks, vec![&[1, 2, 3][..], &[4, 5, 6][..], &[7][..]]);
+    }
+
+    #[test]
+    fn test_from_range() {
+        let var = IntVarValues::from(0..5);
+        assert_eq!(var.as_slice(), &[0, 1, 2, 3, 4]);
+        let var: IntVarValues<i32> = (3..3).into();
+        assert!(var.is_wiped());
+        let var = IntVarValues::from(250u8..255);
+        assert_eq!(var.as_slice(), &[250, 251, 252, 253, 254]);
+    }
+
+    #[test]
+    fn test_set_value_bound_state() {
+        let mut var = IntVarValues::new_from_values(1..=5).unwrap();
+        assert_eq!(var.set_value(5), Ok(IntVariableState::MinBoundChange));
+        let mut var = IntVarValues::new_from_values(1..=5).unwrap();
+        assert_eq!(var.set_value(1), Ok(IntVariableState::MaxBoundChange));
+        let mut var = IntVarValues::new_from_values(1..=5).unwrap();
+        assert_eq!(var.set_value(3), Ok(IntVariableState::BoundsChange));
+        assert_eq!(var.set_value(3), Ok(IntVariableState::NoChange));
+    }
+
+    #[test]
+    fn test_contains_range() {
+        let var = IntVarValues::new_from_values(vec![1, 2, 3, 4, 6, 7]).unwrap();
+        assert_eq!(var.count_in_range(2, 6), 4);
+        assert_eq!(var.count_in_range(8, 9), 0);
+        assert!(var.contains_range(1, 4));
+        assert!(var.contains_range(6, 6));
+        assert!(!var.contains_range(3, 6));
+        assert!(!var.contains_range(0, 2));
+        assert!(var.contains_range(5, 4));
+
+        let var = IntVarValues::new_from_values(vec![i128::MIN, i128::MIN + 1, i128::MAX]).unwrap();
+        assert!(var.contains_range(i128::MIN, i128::MIN + 1));
+        assert!(!var.contains_range(i128::MIN, i128::MAX));
+        assert!(!var.contains_range(i128::MIN + 1, i128::MAX));
+        let big = i128::MAX as u128;
+        let var = IntVarValues::new_from_values(vec![big, big + 1, big + 2, u128::MAX]).unwrap();
+        assert!(var.contains_range(big + 1, big + 2));
+        assert!(var.contains_range(big, big + 2));
+        assert!(!var.contains_range(big, u128::MAX));
+        assert!(!var.contains_range(0, u128::MAX));
+    }
+
+    #[test]
+    fn test_from_domain() {
+        let var = IntVarValues::new_from_values(vec![4, 1, 9]).unwrap();
+        let copy = IntVarValues::from_domain(&var.freeze()).unwrap();
+        assert_eq!(copy, var);
+        assert_eq!(
+            IntVarValues::from_domain(&IntVarValues::<i32>::empty()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_select_midpoint() {
+        let var =
+            IntVarValues::new_from_values(vec![i32::MAX - 10, i32::MAX - 7, i32::MAX]).unwrap();
+        assert_eq!(var.select_midpoint(), Some(i32::MAX - 7));
+        let var = IntVarValues::new_from_values(vec![1, 2, 9]).unwrap();
+        assert_eq!(var.select_midpoint(), Some(2));
+        assert_eq!(IntVarValues::<i32>::empty().select_midpoint(), None);
+    }
+
+    #[test]
+    fn test_keep_largest_run() {
+        let mut var = IntVarValues::new_from_values(vec![1, 2, 3, 7, 8]).unwrap();
+        assert_eq!(
+            var.runs().collect::<Vec<_>>(),
+            vec![&[1, 2, 3][..], &[7, 8][..]]
+        );
+        assert_eq!(var.keep_largest_run(), Ok(IntVariableState::BoundsChange));
+        assert_eq!(var.as_slice(), &[1, 2, 3]);
+        assert_eq!(var.keep_largest_run(), Ok(IntVariableState::NoChange));
+
+        let mut var = IntVarValues::new_from_values(vec![0, 4, 5, 9, 10]).unwrap();
+        assert_eq!(var.keep_largest_run(), Ok(IntVariableState::BoundsChange));
+        assert_eq!(var.as_slice(), &[4, 5]);
+    }
+
+    #[test]
+    fn test_try_fold_values() {
+        let var = IntVarValues::new_from_values(1..=10).unwrap();
+        let capped = var.try_fold_values(0, |sum, v| {
+            if sum + v > 10 {
+                ControlFlow::Break(sum)
+            } else {
+                ControlFlow::Continue(sum + v)
+            }
+        });
+        assert_eq!(capped, 10);
+        let total = var.try_fold_values(0, |sum, v| ControlFlow::Continue(sum + v));
+        assert_eq!(total, 55);
+    }
+
+    #[cfg(feature = "trail")]
+    #[test]
+    fn test_trail_levels() {
+        let mut var = IntVarValues::new_from_values(1..=10).unwrap();
+        var.push_level();
+        var.weak_upperbound(&8).unwrap();
+        var.remove_value(4).unwrap();
+        var.push_level();
+        var.retains_if(|v| v % 2 == 0).unwrap();
+        var.set_value(6).unwrap();
+        assert_eq!(var.as_slice(), &[6]);
+        assert!(var.pop_level());
+        assert_eq!(var.as_slice(), &[1, 2, 3, 5, 6, 7, 8]);
+        assert!(var.pop_level());
+        assert_eq!(var.as_slice(), &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert!(!var.pop_level());
+    }
+
+    #[cfg(feature = "trail")]
+    #[test]
+    fn test_trail_diffs() {
+        let mut var = IntVarValues::new_from_values(0..100).unwrap();
+        for level in 0..10 {
+            var.push_level();
+            for v in (level * 10)..(level * 10 + 5) {
+                var.remove_value(v).unwrap();
+            }
+            assert_eq!(var.trail.last().unwrap().removed.len(), 5);
+            assert!(var.trail.last().unwrap().snapshot.is_none());
+        }
+        var.weak_upperbound(&97).unwrap();
+        assert_eq!(var.trail.last().unwrap().removed.len(), 7);
+        for level in (0..10).rev() {
+            assert!(var.pop_level());
+            assert_eq!(var.size(), 100 - 5 * level);
+            assert!(var.as_slice().windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        assert_eq!(
+            var.as_slice(),
+            IntVarValues::new_from_values(0..100).unwrap().as_slice()
+        );
+
+        var.push_level();
+        var.remove_value(3).unwrap();
+        var.insert_value(200);
+        var.remove_value(200).unwrap();
+        var.remove_value(5).unwrap();
+        assert!(var.pop_level());
+        assert_eq!(var.size(), 100);
+        assert!(!var.contains_range(200, 200));
+    }
+
+    #[test]
+    fn test_assign_min_max() {
+        let mut var = IntVarValues::new_from_values(vec![2, 5, 8]).unwrap();
+        assert_eq!(var.assign_min(), Ok(IntVariableState::MaxBoundChange));
+        assert_eq!(var.value(), Some(&2));
+        assert_eq!(var.assign_min(), Ok(IntVariableState::NoChange));
+        let mut var = IntVarValues::new_from_values(vec![2, 5, 8]).unwrap();
+        assert_eq!(var.assign_max(), Ok(IntVariableState::MinBoundChange));
+        assert_eq!(var.value(), Some(&8));
+        assert_eq!(
+            IntVarValues::<i32>::empty().assign_min(),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_remove_min_max() {
+        let mut var = IntVarValues::new_from_values(vec![2, 5, 8]).unwrap();
+        assert_eq!(var.remove_min(), Ok(IntVariableState::MinBoundChange));
+        assert_eq!(var.min(), Some(&5));
+        assert_eq!(var.remove_max(), Ok(IntVariableState::MaxBoundChange));
+        assert_eq!(var.max(), Some(&5));
+        assert_eq!(var.remove_max(), Err(VariableError::DomainWipeout));
+        assert!(var.is_wiped());
+        assert_eq!(var.remove_min(), Err(VariableError::DomainWipeout));
+    }
+
+    #[test]
+    fn test_builder_exclusions() {
+        let var = IntVarValuesBuilder::<i32>::try_new(1, 100)
+            .unwrap()
+            .without_value(50)
+            .finalize();
+        assert_eq!(var.size(), 99);
+        assert!(!var.as_slice().contains(&50));
+        let var = IntVarValuesBuilder::<i32>::try_new(1, 10)
+            .unwrap()
+            .without_range(3, 8)
+            .without_values(vec![10, 1, 42])
+            .finalize();
+        assert_eq!(var.as_slice(), &[2, 9]);
+        let var = IntVarValuesBuilder::<i32>::try_new(1, 3)
+            .unwrap()
+            .without_range(0, 5)
+            .finalize();
+        assert!(var.is_wiped());
+    }
+
+    #[test]
+    fn test_rank() {
+        let var = IntVarValues::new_from_values(vec![3, 7, 9, 12]).unwrap();
+        assert_eq!(var.rank(&3), Some(0));
+        assert_eq!(var.rank(&12), Some(3));
+        assert_eq!(var.rank(&8), None);
+        assert_eq!(var.value_at(var.rank(&9).unwrap()), Some(9));
+        assert_eq!(var.value_at(4), None);
+    }
+
+    #[test]
+    fn test_partitions_range() {
+        let even = IntVarValues::new_from_values(vec![2, 4, 6]).unwrap();
+        let odd = IntVarValues::new_from_values(vec![1, 3, 5]).unwrap();
+        assert!(!even.intersects(&odd));
+        assert!(even.partitions_range(&odd, 1, 6));
+        assert!(!even.partitions_range(&odd, 1, 7));
+        assert!(!even.partitions_range(&odd, 2, 6));
+        let overlap = IntVarValues::new_from_values(vec![1, 2, 3]).unwrap();
+        assert!(even.intersects(&overlap));
+        assert!(!even.partitions_range(&overlap, 1, 6));
+        let gap = IntVarValues::new_from_values(vec![1, 5]).unwrap();
+        assert!(!even.partitions_range(&gap, 1, 6));
+
+        let low = IntVarValues::new_from_values(vec![i128::MIN]).unwrap();
+        let high = IntVarValues::new_from_values(vec![i128::MAX]).unwrap();
+        assert!(!low.partitions_range(&high, i128::MIN, i128::MAX));
+        let next = IntVarValues::new_from_values(vec![i128::MIN + 1]).unwrap();
+        assert!(low.partitions_range(&next, i128::MIN, i128::MIN + 1));
+        let big = i128::MAX as u128;
+        let low = IntVarValues::new_from_values(vec![big + 1, big + 3]).unwrap();
+        let high = IntVarValues::new_from_values(vec![big + 2]).unwrap();
+        assert!(low.partitions_range(&high, big + 1, big + 3));
+        assert!(!low.partitions_range(&high, big, big + 3));
+        let top = IntVarValues::new_from_values(vec![u128::MAX]).unwrap();
+        assert!(!low.partitions_range(&top, 0, u128::MAX));
+    }
+
+    #[test]
+    fn test_has_system_of_distinct_representatives() {
+        let vars = vec![
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![1]).unwrap(),
+            IntVarValues::new_from_values(vec![2, 3]).unwrap(),
+        ];
+        assert!(IntVarValues::has_system_of_distinct_representatives(&vars));
+        let vars = vec![
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(1..=9).unwrap(),
+        ];
+        assert!(!IntVarValues::has_system_of_distinct_representatives(&vars));
+        assert!(IntVarValues::<i32>::has_system_of_distinct_representatives(
+            &[]
+        ));
+    }
+
+    #[test]
+    fn test_in_sorted_values_into() {
+        let mut scratch = Vec::with_capacity(16);
+        let mut var = IntVarValues::new_from_values(1..=10).unwrap();
+        let buffer = scratch.as_ptr();
+        assert_eq!(
+            var.in_sorted_values_into(vec![0, 2, 3, 5, 7, 11], &mut scratch),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[2, 3, 5, 7]);
+        assert_eq!(var.as_slice().as_ptr(), buffer);
+
+        let buffer = scratch.as_ptr();
+        assert_eq!(
+            var.in_sorted_values_into(vec![2, 5, 7], &mut scratch),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(var.as_slice(), &[2, 5, 7]);
+        assert_eq!(var.as_slice().as_ptr(), buffer);
+        assert_eq!(
+            var.in_sorted_values_into(vec![1, 3], &mut scratch),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_set_value_entailing() {
+        use crate::Propagation;
+
+        let mut var = IntVarValues::new_from_values(1..=5).unwrap();
+        let state = var.weak_upperbound(&4).unwrap();
+        assert_eq!(
+            Propagation::new(state, var.is_affected()),
+            Propagation::Changed(IntVariableState::BoundsChange)
+        );
+        let (state, fixed) = var.set_value_entailing(3).unwrap();
+        assert!(fixed);
+        assert_eq!(
+            Propagation::new(state, fixed),
+            Propagation::Fixed(IntVariableState::BoundsChange)
+        );
+        assert_eq!(
+            var.set_value_entailing(3),
+            Ok((IntVariableState::NoChange, true))
+        );
+        assert_eq!(
+            Propagation::new(IntVariableState::NoChange, true),
+            Propagation::NoChange
+        );
+        assert_eq!(
+            var.set_value_entailing(4),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_branch_candidates() {
+        use crate::int_var::BranchStrategy;
+
+        let var = IntVarValues::new_from_values(vec![1, 2, 3, 5, 8, 13, 21]).unwrap();
+        let candidates = |strategy| var.branch_candidates(strategy).collect::<Vec<_>>();
+        assert_eq!(
+            candidates(BranchStrategy::MinFirst),
+            vec![1, 2, 3, 5, 8, 13, 21]
+        );
+        assert_eq!(
+            candidates(BranchStrategy::MaxFirst),
+            vec![21, 13, 8, 5, 3, 2, 1]
+        );
+        assert_eq!(
+            candidates(BranchStrategy::Median),
+            vec![5, 3, 8, 2, 13, 1, 21]
+        );
+        assert_eq!(
+            candidates(BranchStrategy::Bisect),
+            vec![5, 2, 13, 1, 3, 8, 21]
+        );
+        let var = IntVarValues::new_from_values(1..=4).unwrap();
+        assert_eq!(
+            var.branch_candidates(BranchStrategy::Median)
+                .collect::<Vec<_>>(),
+            vec![2, 1, 3, 4]
+        );
+        assert_eq!(
+            var.branch_candidates(BranchStrategy::Bisect)
+                .collect::<Vec<_>>(),
+            vec![2, 1, 3, 4]
+        );
+        let var = IntVarValues::<i32>::empty();
+        for strategy in [
+            BranchStrategy::MinFirst,
+            BranchStrategy::MaxFirst,
+            BranchStrategy::Median,
+            BranchStrategy::Bisect,
+        ] {
+            assert_eq!(var.branch_candidates(strategy).next(), None);
+        }
+    }
+
+    #[test]
+    fn test_equal_bounds_only() {
+        let mut x = IntVarValues::new_from_values(vec![1, 3, 4, 6, 9]).unwrap();
+        let mut y = IntVarValues::new_from_values(vec![2, 3, 5, 6, 8]).unwrap();
+        assert_eq!(
+            x.equal_bounds_only(&mut y),
+            Ok((
+                IntVariableState::BoundsChange,
+                IntVariableState::BoundsChange
+            ))
+        );
+        assert_eq!(x.as_slice(), &[3, 4, 6]);
+        assert_eq!(y.as_slice(), &[3, 5, 6]);
+        assert_eq!(
+            x.equal_bounds_only(&mut y),
+            Ok((IntVariableState::NoChange, IntVariableState::NoChange))
+        );
+
+        let mut x = IntVarValues::new_from_values(vec![1, 3, 5]).unwrap();
+        let mut y = IntVarValues::new_from_values(vec![1, 4, 5]).unwrap();
+        assert_eq!(
+            x.equal_bounds_only(&mut y),
+            Ok((IntVariableState::NoChange, IntVariableState::NoChange))
+        );
+        assert_ne!(x, y);
+
+        let mut x = IntVarValues::new_from_values(1..=3).unwrap();
+        let mut y = IntVarValues::new_from_values(5..=7).unwrap();
+        assert_eq!(
+            x.equal_bounds_only(&mut y),
+            Err(VariableError::BoundWipeout { lower: true })
+        );
+    }
+
+    #[test]
+    fn test_plus_constant() {
+        let var = IntVarValues::new_from_values(vec![-1, 0, 4, 7]).unwrap();
+        assert_eq!(var.plus_constant(3).unwrap().as_slice(), &[2, 3, 7, 10]);
+        assert_eq!(var.plus_constant(-2).unwrap().as_slice(), &[-3, -2, 2, 5]);
+        assert_eq!(var.as_slice(), &[-1, 0, 4, 7]);
+        assert_eq!(
+            var.plus_constant(i32::MAX - 5),
+            Err(VariableError::Overflow)
+        );
+        assert_eq!(var.plus_constant(i32::MIN), Err(VariableError::Overflow));
+    }
+
+    #[test]
+    fn test_fixed_value() {
+        let mut var = IntVarValues::new_from_values(vec![2, 5, 8]).unwrap();
+        assert_eq!(var.fixed_value(), None);
+        assert_eq!(var.fixed_value(), var.value());
+        var.set_value(5).unwrap();
+        assert_eq!(var.fixed_value(), Some(&5));
+        assert_eq!(var.fixed_value(), var.value());
+    }
+
+    #[test]
+    fn test_remove_values() {
+        let removed = vec![9, 2, 14, 7, 2, 1];
+        let mut var = IntVarValues::new_from_values(1..=10).unwrap();
+        let mut expected = var.clone();
+        for &value in removed.iter() {
+            expected.remove_value(value).unwrap();
+        }
+        assert_eq!(
+            var.remove_values(removed),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var, expected);
+        assert_eq!(var.as_slice(), &[3, 4, 5, 6, 8, 10]);
+        assert_eq!(
+            var.remove_values(vec![5, 4]),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(
+            var.remove_values(vec![0, 11]),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(
+            var.remove_values(vec![10, 8, 6, 3]),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_display_truncated() {
+        let var =
+            IntVarValues::new_from_values((0..100).filter(|v| v % 10 < 3 || v % 10 == 5)).unwrap();
+        assert_eq!(var.display_truncated(3), "0..2, {5}, 10..12, …(+17 more)");
+        assert_eq!(var.display_truncated(0), "…(+20 more)");
+        assert!(!var.display_truncated(20).contains("more"));
+        let var = IntVarValues::new_from_values(vec![1, 2, 3, 7]).unwrap();
+        assert_eq!(var.display_truncated(2), "1..3, {7}");
+    }
+
+    #[test]
+    fn test_equals_values() {
+        let var = IntVarValues::new_from_values(vec![1, 4, 6]).unwrap();
+        assert!(var.equals_values(&[1, 4, 6]));
+        assert!(var.equals_values(&[6, 1, 4, 1]));
+        assert!(!var.equals_values(&[1, 4]));
+        assert!(!var.equals_values(&[1, 4, 5]));
+    }
+
+    #[test]
+    fn test_count_if() {
+        let var = IntVarValues::new_from_values(vec![1, 2, 4, 7, 8, 10]).unwrap();
+        assert_eq!(var.count_if(|v| v % 2 == 0), 4);
+        assert_eq!(var.count_if(|v| *v > 10), 0);
+        assert_eq!(var.size(), 6);
+    }
+
+    #[test]
+    fn test_constant() {
+        let var = IntVarValues::constant(5);
+        assert_eq!(var.value(), Some(&5));
+        assert!(var.is_affected());
+        assert_eq!(Some(var), IntVarValues::<i32>::try_new(5, 5));
+    }
+
+    #[test]
+    fn test_decimate() {
+        let mut var = IntVarValues::new_from_values(1..=10).unwrap();
+        assert_eq!(var.decimate(2), Ok(IntVariableState::BoundsChange));
+        assert_eq!(var.as_slice(), &[1, 3, 5, 7, 9]);
+        assert_eq!(var.decimate(1), Ok(IntVariableState::NoChange));
+        assert_eq!(var.decimate(3), Ok(IntVariableState::BoundsChange));
+        assert_eq!(var.as_slice(), &[1, 7]);
+        assert_eq!(var.decimate(0), Err(VariableError::InvalidArgument));
+        assert_eq!(var.as_slice(), &[1, 7]);
+    }
+
+    #[test]
+    fn test_summary() {
+        let var = IntVarValues::new_from_values(vec![-2, 3, 4, 10]).unwrap();
+        assert_eq!(
+            var.summary(),
+            Some(DomainSummary {
+                min: -2,
+                max: 10,
+                count: 4,
+                sum: 15,
+            })
+        );
+        let mut var = IntVarValues::constant(7);
+        assert_eq!(var.summary().map(|summary| summary.sum), Some(7));
+        var.remove_value(7).unwrap_err();
+        assert_eq!(var.summary(), None);
+    }
+
+    #[test]
+    fn test_retain_where_index() {
+        let mut var = IntVarValues::new_from_values(vec![2, 3, 5, 7, 11, 13]).unwrap();
+        assert_eq!(
+            var.retain_where_index(|rank, _| rank % 2 == 0),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[2, 5, 11]);
+
+        let squares: BTreeSet<_> = (0..=10).map(|v| v * v).collect();
+        let mut var = IntVarValues::new_from_values(1..=50).unwrap();
+        assert_eq!(
+            var.retain_where_index(|_, v| squares.contains(v)),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[1, 4, 9, 16, 25, 36, 49]);
+        assert_eq!(
+            var.retain_where_index(|rank, v| rank > 10 || *v > 100),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_bound_wipeout() {
+        let mut var = IntVarValues::new_from_values(vec![3, 5, 7]).unwrap();
+        assert_eq!(
+            var.weak_lowerbound(&8),
+            Err(VariableError::BoundWipeout { lower: true })
+        );
+        assert_eq!(
+            var.strict_lowerbound(&7),
+            Err(VariableError::BoundWipeout { lower: true })
+        );
+        assert_eq!(
+            var.weak_upperbound(&2),
+            Err(VariableError::BoundWipeout { lower: false })
+        );
+        assert_eq!(
+            var.strict_upperbound(&3),
+            Err(VariableError::BoundWipeout { lower: false })
+        );
+        assert_eq!(var.remove_if(|_| true), Err(VariableError::DomainWipeout));
+    }
+
+    #[test]
+    fn test_bitmask() {
+        let var = IntVarValues::from_bitmask(0b1011_0001, -3).unwrap();
+        assert_eq!(var.as_slice(), &[-3, 1, 2, 4]);
+        assert_eq!(var.to_bitmask(-3), Some(0b1011_0001));
+        assert_eq!(var.to_bitmask(-4), Some(0b1_0110_0010));
+        let var = IntVarValues::from_bitmask(u64::MAX, 0).unwrap();
+        assert_eq!(var.size(), 64);
+        assert_eq!(var.to_bitmask(0), Some(u64::MAX));
+        assert_eq!(IntVarValues::from_bitmask(0, 5), None);
+        assert_eq!(IntVarValues::from_bitmask(0b10, i64::MAX), None);
+
+        let var = IntVarValues::new_from_values(vec![0i64, 64]).unwrap();
+        assert_eq!(var.to_bitmask(0), None);
+        assert_eq!(var.to_bitmask(1), None);
+        assert_eq!(var.to_bitmask(i64::MIN), None);
+    }
+
+    #[test]
+    fn test_try_prune() {
+        let mut var = IntVarValues::new_from_values(1..=10).unwrap();
+        let generation = var.generation();
+        assert_eq!(
+            var.try_prune(generation, PruneOp::WeakUpperbound(6)),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            var.try_prune(generation, PruneOp::RemoveValue(3)),
+            Err(PruneConflict::Stale {
+                current: var.generation()
+            })
+        );
+        assert_eq!(var.size(), 6);
+
+        let generation = var.generation();
+        var.weak_lowerbound(&1).unwrap();
+        assert_eq!(var.generation(), generation);
+        let shared = var.clone();
+        assert_eq!(
+            var.try_prune(shared.generation(), PruneOp::RemoveValue(3)),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_ne!(var.generation(), generation);
+        assert_eq!(
+            var.try_prune(var.generation(), PruneOp::SetValue(3)),
+            Err(PruneConflict::Error(VariableError::DomainWipeout))
+        );
+
+        let mut left = IntVarValues::new_from_values(1..=10).unwrap();
+        let mut right = left.clone();
+        left.remove_value(2).unwrap();
+        right.remove_value(3).unwrap();
+        assert_eq!(
+            right.try_prune(left.generation(), PruneOp::RemoveValue(4)),
+            Err(PruneConflict::Stale {
+                current: right.generation()
+            })
+        );
+        assert_eq!(right.size(), 9);
+    }
+
+    #[test]
+    fn test_retain_arithmetic() {
+        let mut var = IntVarValues::new_from_values(-5..=12).unwrap();
+        assert_eq!(
+            var.retain_arithmetic(1, 3),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[1, 4, 7, 10]);
+        assert_eq!(var.retain_arithmetic(-2, 3), Ok(IntVariableState::NoChange));
+
+        let mut var = IntVarValues::new_from_values(-5..=12).unwrap();
+        assert_eq!(
+            var.retain_arithmetic(7, -4),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[-5, -1, 3, 7]);
+        assert_eq!(
+            var.retain_arithmetic(0, 0),
+            Err(VariableError::InvalidArgument)
+        );
+        assert_eq!(
+            var.retain_arithmetic(8, 1),
+            Err(VariableError::DomainWipeout)
+        );
+
+        let mut var = IntVarValues::new_from_values(vec![i32::MIN, -1, 0, i32::MAX]).unwrap();
+        assert_eq!(
+            var.retain_arithmetic(i32::MIN, 1),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(
+            var.retain_arithmetic(i32::MAX, -1),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(
+            var.retain_arithmetic(i32::MIN, 2),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[i32::MIN, 0]);
+        assert_eq!(
+            var.retain_arithmetic(i32::MAX, -i32::MAX),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(var.as_slice(), &[0]);
+    }
+
+    #[test]
+    fn test_minkowski_sum() {
+        let a = IntVarValues::new_from_values(vec![1, 2]).unwrap();
+        let b = IntVarValues::new_from_values(vec![10, 20]).unwrap();
+        assert_eq!(
+            minkowski_sum(&a, &b, 10).unwrap().as_slice(),
+            &[11, 12, 21, 22]
+        );
+        assert_eq!(minkowski_sum(&a, &b, 3), None);
+        assert!(minkowski_sum(&a, &b, 4).is_some());
+
+        let a = IntVarValues::new_from_values(vec![-3, 0, 1, 2, 8]).unwrap();
+        let b = IntVarValues::new_from_values(vec![0, 1, 5, 6, 7]).unwrap();
+        let expected = a
+            .as_slice()
+            .iter()
+            .flat_map(|&v| b.as_slice().iter().map(move |&w| v + w))
+            .collect::<BTreeSet<_>>();
+        assert!(minkowski_sum(&a, &b, 100)
+            .unwrap()
+            .as_slice()
+            .iter()
+            .eq(expected.iter()));
+
+        let a = IntVarValues::new_from_values(0..1000).unwrap();
+        let b = IntVarValues::new_from_values(-1000..0).unwrap();
+        let sum = minkowski_sum(&a, &b, 2000).unwrap();
+        assert_eq!(
+            (sum.size(), sum.min(), sum.max()),
+            (1999, Some(&-1000), Some(&998))
+        );
+    }
+
+    #[test]
+    fn test_retain_sum_support() {
+        let mut x = IntVarValues::new_from_values(0..=5).unwrap();
+        let y = IntVarValues::new_from_values(vec![10, 20]).unwrap();
+        let z = minkowski_sum(&x, &y, 100).unwrap();
+        assert_eq!(x.retain_sum_support(&y, &z), Ok(IntVariableState::NoChange));
+
+        let z = IntVarValues::new_from_values(vec![11, 13, 24, 30]).unwrap();
+        assert_eq!(
+            x.retain_sum_support(&y, &z),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(x.as_slice(), &[1, 3, 4]);
+        let z = IntVarValues::new_from_values(vec![12, 15, 22]).unwrap();
+        assert_eq!(
+            x.retain_sum_support(&y, &z),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[cfg(all(feature = "observer", feature = "graph"))]
+    #[test]
+    fn test_queue_observer() {
+        use super::CruspIntVarValues;
+        use crate::domains::{OrderedDomainObserver, PrunableDomainObserver};
+        use crate::queue::{DirtyQueue, QueueObserver};
+        use crusp_core::VariableId;
+
+        let (x_id, y_id, z_id) = (VariableId::new(0), VariableId::new(1), VariableId::new(2));
+        let mut x = CruspIntVarValues {
+            id: x_id,
+            domain: (1..=9).collect(),
+        };
+        let mut y = CruspIntVarValues {
+            id: y_id,
+            domain: vec![2, 4, 6],
+        };
+        let mut z = CruspIntVarValues {
+            id: z_id,
+            domain: vec![1, 3],
+        };
+        let mut queue = DirtyQueue::new();
+        let mut observer = QueueObserver::new(&mut queue);
+        assert_eq!(
+            x.weak_upperbound(&mut observer, &5),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(
+            y.remove_value(&mut observer, 5),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(
+            y.remove_value(&mut observer, 4),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(
+            x.remove_value(&mut observer, 3),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(
+            z.strict_lowerbound(&mut observer, &3),
+            Err(VariableError::BoundWipeout { lower: true })
+        );
+        assert_eq!(
+            y.weak_lowerbound(&mut observer, &4),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(observer.failed(), Some(z_id));
+        assert_eq!(queue.pop(), Some(x_id));
+        assert_eq!(queue.pop(), Some(y_id));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[cfg(feature = "observer")]
+    #[test]
+    fn test_batch_observer() {
+        use super::CruspIntVarValues;
+        use crate::domains::{OrderedDomainObserver, PrunableDomainObserver};
+        use crate::observer::{BatchObserver, Recorder};
+        use crusp_core::VariableId;
+
+        let id = VariableId::new(0);
+        let mut var = CruspIntVarValues {
+            id,
+            domain: (1..=9).collect(),
+        };
+        let mut recorder = Recorder::default();
+        let mut batch = BatchObserver::new(&mut recorder);
+        assert_eq!(
+            var.weak_upperbound(&mut batch, &7),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(
+            var.remove_value(&mut batch, 4),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(
+            var.strict_lowerbound(&mut batch, &2),
+            Ok(IntVariableState::BoundsChange)
+        );
+        batch.flush().unwrap();
+        assert_eq!(var.domain, vec![3, 5, 6, 7]);
+        assert_eq!(
+            recorder.events,
+            vec![(id, Ok(IntVariableState::ValuesChange))]
+        );
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    #[cfg(feature = "observer")]
+    use super::CruspIntVarValues;
+    use super::IntVarValues;
+    use crate::domains::{
+        AssignableDomain, FromValuesDomain, IterableDomain, OrderedDomain, PrunableDomain,
+    };
+    #[cfg(feature = "observer")]
+    use crate::domains::{
+        AssignableDomainObserver, EqualDomain, EqualDomainObserver, NoOpObserver,
+        OrderedDomainObserver, PrunableDomainObserver,
+    };
+    use crate::int_var::IntVariableState;
+    use crate::VariableError;
+    #[cfg(feature = "observer")]
+    use crusp_core::VariableId;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        SetValue(i32),
+        StrictUpperbound(i32),
+        WeakUpperbound(i32),
+        StrictLowerbound(i32),
+        WeakLowerbound(i32),
+        RemoveValue(i32),
+        RemoveIf(i32, i32),
+        RetainsIf(i32, i32),
+        InValues(Vec<i32>),
+    }
+
+    // Small ranges make collisions and boundary cases dense.
+    fn value() -> impl Strategy<Value = i32> {
+        -2..12
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            value().prop_map(Op::SetValue),
+            value().prop_map(Op::StrictUpperbound),
+            value().prop_map(Op::WeakUpperbound),
+            value().prop_map(Op::StrictLowerbound),
+            value().prop_map(Op::WeakLowerbound),
+            value().prop_map(Op::RemoveValue),
+            (2..4, 0..4).prop_map(|(m, r)| Op::RemoveIf(m, r)),
+            (2..4, 0..4).prop_map(|(m, r)| Op::RetainsIf(m, r)),
+            vec(value(), 0..8).prop_map(Op::InValues),
+        ]
+    }
+
+    fn apply(
+        var: &mut IntVarValues<i32>,
+        oracle: &BTreeSet<i32>,
+        op: &Op,
+    ) -> (Result<IntVariableState, VariableError>, BTreeSet<i32>) {
+        let filter = |pred: &dyn Fn(&i32) -> bool| oracle.iter().copied().filter(pred).collect();
+        match *op {
+            Op::SetValue(v) => (var.set_value(v), filter(&|&x| x == v)),
+            Op::StrictUpperbound(v) => (var.strict_upperbound(&v), filter(&|&x| x < v)),
+            Op::WeakUpperbound(v) => (var.weak_upperbound(&v), filter(&|&x| x <= v)),
+            Op::StrictLowerbound(v) => (var.strict_lowerbound(&v), filter(&|&x| x > v)),
+            Op::WeakLowerbound(v) => (var.weak_lowerbound(&v), filter(&|&x| x >= v)),
+            Op::RemoveValue(v) => (var.remove_value(v), filter(&|&x| x != v)),
+            Op::RemoveIf(m, r) => (
+                var.remove_if(|x| x.rem_euclid(m) == r),
+                filter(&|x| x.rem_euclid(m) != r),
+            ),
+            Op::RetainsIf(m, r) => (
+                var.retains_if(|x| x.rem_euclid(m) == r),
+                filter(&|x| x.rem_euclid(m) == r),
+            ),
+            Op::InValues(ref values) => (
+                var.in_values(values.iter().copied()),
+                filter(&|x| values.contains(x)),
+            ),
+        }
+    }
+
+    // Error returned by `op` when it wipes out the domain.
+    fn wipeout(op: &Op) -> VariableError {
+        match *op {
+            Op::StrictUpperbound(_) | Op::WeakUpperbound(_) => {
+                VariableError::BoundWipeout { lower: false }
+            }
+            Op::StrictLowerbound(_) | Op::WeakLowerbound(_) => {
+                VariableError::BoundWipeout { lower: true }
+            }
+            _ => VariableError::DomainWipeout,
+        }
+    }
+
+    // Same as `apply` on the observer version of the variable.
+    #[cfg(feature = "observer")]
+    fn apply_observed(
+        var: &mut CruspIntVarValues<i32>,
+        op: &Op,
+    ) -> Result<IntVariableState, VariableError> {
+        let observer = &mut NoOpObserver::new();
+        match *op {
+            Op::SetValue(v) => var.set_value(observer, v),
+            Op::StrictUpperbound(v) => var.strict_upperbound(observer, &v),
+            Op::WeakUpperbound(v) => var.weak_upperbound(observer, &v),
+            Op::StrictLowerbound(v) => var.strict_lowerbound(observer, &v),
+            Op::WeakLowerbound(v) => var.weak_lowerbound(observer, &v),
+            Op::RemoveValue(v) => var.remove_value(observer, v),
+            Op::RemoveIf(m, r) => var.remove_if(observer, |x| x.rem_euclid(m) == r),
+            Op::RetainsIf(m, r) => var.retains_if(observer, |x| x.rem_euclid(m) == r),
+            Op::InValues(ref values) => var.in_values(observer, values.iter().copied()),
+        }
+    }
+
+    fn state_matches(state: IntVariableState, prev: &BTreeSet<i32>, next: &BTreeSet<i32>) -> bool {
+        let min_change = prev.iter().next() != next.iter().next();
+        let max_change = prev.iter().next_back() != next.iter().next_back();
+        let size_change = prev.len() != next.len();
+        match state {
+            IntVariableState::NoChange => !size_change,
+            IntVariableState::MinBoundChange => min_change && !max_change,
+            IntVariableState::MaxBoundChange => max_change && !min_change,
+            IntVariableState::BoundsChange => min_change || max_change,
+            IntVariableState::ValuesChange => size_change && !min_change && !max_change,
+            _ => false,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_new_from_values_match_btreeset(values in vec(value(), 1..50)) {
+            let var = IntVarValues::new_from_values(values.iter().copied()).unwrap();
+            let oracle: BTreeSet<_> = values.into_iter().collect();
+            prop_assert!(var.iter().copied().eq(oracle.into_iter()));
+        }
+
+        #[test]
+        fn test_operations_match_oracle(
+            values in vec(value(), 1..10),
+            ops in vec(op(), 1..20),
+        ) {
+            let mut var = IntVarValues::new_from_values(values.iter().copied()).unwrap();
+            let mut oracle: BTreeSet<_> = values.into_iter().collect();
+            for op in ops.iter() {
+                let (state, next) = apply(&mut var, &oracle, op);
+                if next.is_empty() {
+                    prop_assert_eq!(state, Err(wipeout(op)), "{:?}", op);
+                    break;
+                }
+                let state = state.unwrap();
+                prop_assert!(var.iter().copied().eq(next.iter().copied()), "{:?}", op);
+                prop_assert!(state_matches(state, &oracle, &next), "{:?} {:?}", op, state);
+                oracle = next;
+            }
+        }
+
+        #[cfg(feature = "observer")]
+        #[test]
+        fn test_observer_matches_plain(
+            values in vec(value(), 1..10),
+            others in vec(value(), 1..10),
+            ops in vec(op(), 1..20),
+        ) {
+            let mut var = IntVarValues::new_from_values(values.iter().copied()).unwrap();
+            let mut observed = CruspIntVarValues {
+                id: VariableId::new(0),
+                domain: var.as_slice().to_vec(),
+            };
+            for op in ops.iter() {
+                let state = apply(&mut var, &BTreeSet::new(), op).0;
+                prop_assert_eq!(apply_observed(&mut observed, op), state.clone(), "{:?}", op);
+                if state.is_err() {
+                    return Ok(());
+                }
+                prop_assert_eq!(var.as_slice(), &observed.domain[..], "{:?}", op);
+            }
+            let mut other = IntVarValues::new_from_values(others.iter().copied()).unwrap();
+            let mut other_observed = CruspIntVarValues {
+                id: VariableId::new(1),
+                domain: other.as_slice().to_vec(),
+            };
+            prop_assert_eq!(
+                EqualDomainObserver::equal(
+                    &mut observed,
+                    &mut NoOpObserver::new(),
+                    &mut other_observed,
+                ),
+                EqualDomain::equal(&mut var, &mut other)
+            );
+            prop_assert_eq!(var.as_slice(), &observed.domain[..]);
+            prop_assert_eq!(other.as_slice(), &other_observed.domain[..]);
+        }
     }
 }
@@ -1,11 +1,21 @@
 #[cfg(feature = "observer")]
 use crusp_core::VariableId;
 use crusp_core::{Nullable, Subsumed};
+use domains::FiniteDomain;
 use std::marker::PhantomData;
 
+pub mod array;
 pub mod bool_var;
 pub mod domains;
 pub mod int_var;
+#[cfg(feature = "observer")]
+pub mod observer;
+pub mod parse;
+pub mod propagators;
+#[cfg(feature = "graph")]
+pub mod queue;
+#[cfg(feature = "trace")]
+pub mod trace;
 
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -33,12 +43,66 @@ pub enum SetVariableState {
 pub enum VariableError {
     /// The domain of the variable is empty.
     DomainWipeout,
+    /// The domain of the variable is empty after the update of a bound: the lower bound is
+    /// greater than the maximal value if `lower` is `true`, otherwise the upper bound is lower
+    /// than the minimal value.
+    BoundWipeout { lower: bool },
+    /// An argument of the operation is not valid (e.g. a division by zero).
+    InvalidArgument,
+    /// A value of the domain does not fit in the type of the variable after the operation.
+    Overflow,
+}
+
+impl std::fmt::Display for VariableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VariableError::DomainWipeout => write!(f, "domain wipeout"),
+            VariableError::BoundWipeout { lower: true } => write!(f, "lower bound wipeout"),
+            VariableError::BoundWipeout { lower: false } => write!(f, "upper bound wipeout"),
+            VariableError::InvalidArgument => write!(f, "invalid argument"),
+            VariableError::Overflow => write!(f, "overflow"),
+        }
+    }
 }
+
+impl std::error::Error for VariableError {}
 pub trait VariableState:
     std::ops::BitOr<Output = Self> + Subsumed + Sized + Nullable + Eq + PartialEq
 {
 }
 
+/// Outcome of a domain update that tells a mere change apart from the variable becoming
+/// fixed, in which case the constraints over it can be considered as entailed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Propagation<VState> {
+    /// The domain of the variable has not been updated.
+    NoChange,
+    /// The domain of the variable has been updated but the variable is not fixed.
+    Changed(VState),
+    /// The domain of the variable has been updated and the variable is fixed.
+    Fixed(VState),
+}
+
+impl<VState> Propagation<VState>
+where
+    VState: VariableState,
+{
+    /// Builds the outcome of an update from the state of the variable.
+    ///
+    /// # Parameters
+    /// * `state` - The state returned by the update.
+    /// * `fixed` - Whether the variable is fixed after the update.
+    pub fn new(state: VState, fixed: bool) -> Self {
+        if state.is_null() {
+            Propagation::NoChange
+        } else if fixed {
+            Propagation::Fixed(state)
+        } else {
+            Propagation::Changed(state)
+        }
+    }
+}
+
 #[cfg(feature = "observer")]
 pub trait CruspVariable<Type>: Variable<Type> {
     /// Returns the id of the variable
@@ -89,14 +153,26 @@ where
     /// Returns a reference to the variable at that position without doing bounds check.
     fn get_unchecked(&self, position: usize) -> &ArrayVar;
     /// Returns an iterator over the variables.
-    fn iter<'array>(&'array self) -> Box<dyn Iterator<Item = &ArrayVar> + 'array>;
+    fn iter<'array>(&'array self) -> Box<dyn Iterator<Item = &'array ArrayVar> + 'array>;
     /// Returns an iterator that allows modifying each variable.
-    fn iter_mut<'array>(&'array mut self) -> Box<dyn Iterator<Item = &mut ArrayVar> + 'array>;
+    fn iter_mut<'array>(
+        &'array mut self,
+    ) -> Box<dyn Iterator<Item = &'array mut ArrayVar> + 'array>;
     /// Returns the number of variables.
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool {
         self.len() == 0usize
     }
+    /// Returns the indices of the variables sorted by increasing domain size (first-fail
+    /// order), ties being broken by index.
+    fn indices_by_domain_size(&self) -> Vec<usize>
+    where
+        ArrayVar: FiniteDomain<Type>,
+    {
+        let mut indices: Vec<_> = (0..self.len()).collect();
+        indices.sort_by_key(|&index| self.get_unchecked(index).size());
+        indices
+    }
 }
 
 /// Represents an array of `Variable`.
@@ -156,11 +232,11 @@ where
         unsafe { self.variables.get_unchecked(position) }
     }
 
-    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &Var> + 'a> {
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Var> + 'a> {
         Box::new(self.variables.iter())
     }
 
-    fn iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &mut Var> + 'a> {
+    fn iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut Var> + 'a> {
         Box::new(self.variables.iter_mut())
     }
 
@@ -218,11 +294,11 @@ where
         unsafe { &(**self.variables.get_unchecked(position)) }
     }
 
-    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &Var> + 'a> {
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Var> + 'a> {
         unsafe { Box::new(self.variables.iter().map(|&var| &*var)) }
     }
 
-    fn iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &mut Var> + 'a> {
+    fn iter_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut Var> + 'a> {
         unsafe { Box::new(self.variables.iter_mut().map(|&mut var| &mut *var)) }
     }
 
@@ -230,3 +306,40 @@ where
         self.variables.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayOfVariables, ArrayOfVars, VariableError};
+    use crate::domains::FromValuesDomain;
+    use crate::int_var::IntVarValues;
+
+    #[test]
+    fn test_indices_by_domain_size() {
+        let vars = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(1..=4).unwrap(),
+            IntVarValues::new_from_values(vec![1]).unwrap(),
+            IntVarValues::new_from_values(1..=3).unwrap(),
+            IntVarValues::new_from_values(vec![2, 7]).unwrap(),
+            IntVarValues::new_from_values(vec![5]).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(vars.indices_by_domain_size(), vec![1, 4, 3, 2, 0]);
+    }
+
+    #[test]
+    fn test_variable_error_display() {
+        assert_eq!(VariableError::DomainWipeout.to_string(), "domain wipeout");
+        assert_eq!(
+            VariableError::InvalidArgument.to_string(),
+            "invalid argument"
+        );
+        assert_eq!(
+            VariableError::BoundWipeout { lower: true }.to_string(),
+            "lower bound wipeout"
+        );
+        assert_eq!(VariableError::Overflow.to_string(), "overflow");
+        let wipeout =
+            || -> Result<(), Box<dyn std::error::Error>> { Err(VariableError::DomainWipeout)? };
+        assert_eq!(wipeout().unwrap_err().to_string(), "domain wipeout");
+    }
+}
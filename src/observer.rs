@@ -0,0 +1,154 @@
+#[cfg(test)]
+use crate::int_var::IntVariableState;
+use crate::{VariableError, VariableObserver, VariableState};
+use crusp_core::VariableId;
+use std::collections::HashMap;
+
+/// Observer that merges the changes of each variable and forwards them to an inner observer
+/// only on `flush`, so that a variable pruned several times in a propagation step is woken up
+/// once. Errors are forwarded immediately.
+/// The changes still pending when the observer is dropped are lost: `flush` has to be called
+/// at the end of each propagation step.
+#[derive(Debug)]
+pub struct BatchObserver<'a, State, Observer> {
+    inner: &'a mut Observer,
+    changes: HashMap<VariableId, State>,
+    order: Vec<VariableId>,
+}
+
+impl<'a, State, Observer> BatchObserver<'a, State, Observer>
+where
+    State: VariableState + Copy,
+    Observer: VariableObserver<State>,
+{
+    /// Creates an observer that forwards the merged changes to `inner`.
+    ///
+    /// # Parameters
+    /// * `inner` - The observer receiving the merged changes.
+    pub fn new(inner: &'a mut Observer) -> Self {
+        BatchObserver {
+            inner,
+            changes: HashMap::new(),
+            order: vec![],
+        }
+    }
+
+    /// Forwards the merged change of each variable to the inner observer, in the order of the
+    /// first change of the variables, and clears the batch.
+    /// Returns the first error returned by the inner observer.
+    pub fn flush(&mut self) -> Result<(), VariableError> {
+        for vid in self.order.drain(..) {
+            let state = self.changes.remove(&vid).unwrap();
+            self.inner.push_change(vid, state)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, State, Observer> VariableObserver<State> for BatchObserver<'a, State, Observer>
+where
+    State: VariableState + Copy,
+    Observer: VariableObserver<State>,
+{
+    fn push(
+        &mut self,
+        vid: VariableId,
+        event: Result<State, VariableError>,
+    ) -> Result<State, VariableError> {
+        match event {
+            Ok(state) => self.push_change(vid, state),
+            Err(error) => self.push_error(vid, error),
+        }
+    }
+
+    fn push_change(&mut self, vid: VariableId, event: State) -> Result<State, VariableError> {
+        if !event.is_null() {
+            match self.changes.get_mut(&vid) {
+                Some(state) => *state = *state | event,
+                None => {
+                    self.changes.insert(vid, event);
+                    self.order.push(vid);
+                }
+            }
+        }
+        Ok(event)
+    }
+
+    fn push_error(
+        &mut self,
+        vid: VariableId,
+        event: VariableError,
+    ) -> Result<State, VariableError> {
+        self.inner.push_error(vid, event)
+    }
+}
+
+// Observer that records the events it receives, for the tests.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct Recorder {
+    pub(crate) events: Vec<(VariableId, Result<IntVariableState, VariableError>)>,
+}
+
+#[cfg(test)]
+impl VariableObserver<IntVariableState> for Recorder {
+    fn push(
+        &mut self,
+        vid: VariableId,
+        event: Result<IntVariableState, VariableError>,
+    ) -> Result<IntVariableState, VariableError> {
+        self.events.push((vid, event.clone()));
+        event
+    }
+
+    fn push_change(
+        &mut self,
+        vid: VariableId,
+        event: IntVariableState,
+    ) -> Result<IntVariableState, VariableError> {
+        self.push(vid, Ok(event))
+    }
+
+    fn push_error(
+        &mut self,
+        vid: VariableId,
+        event: VariableError,
+    ) -> Result<IntVariableState, VariableError> {
+        self.push(vid, Err(event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_observer() {
+        let mut recorder = Recorder::default();
+        let (x, y) = (VariableId::new(0), VariableId::new(1));
+        let mut batch = BatchObserver::new(&mut recorder);
+        batch
+            .push_change(x, IntVariableState::MaxBoundChange)
+            .unwrap();
+        batch.push(y, Ok(IntVariableState::NoChange)).unwrap();
+        batch
+            .push_change(x, IntVariableState::ValuesChange)
+            .unwrap();
+        batch
+            .push_change(x, IntVariableState::MinBoundChange)
+            .unwrap();
+        batch.flush().unwrap();
+        batch.flush().unwrap();
+        assert_eq!(
+            recorder.events,
+            vec![(x, Ok(IntVariableState::ValuesChange))]
+        );
+
+        let mut batch = BatchObserver::new(&mut recorder);
+        assert_eq!(
+            batch.push_error(y, VariableError::DomainWipeout),
+            Err(VariableError::DomainWipeout)
+        );
+        assert_eq!(recorder.events.len(), 2);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::bool_var::BoolVar;
+use crate::domains::{AssignableDomain, FromValuesDomain};
+use crate::int_var::IntVarValues;
+use crate::{ArrayOfVariables, ArrayOfVars, Variable};
+use std::fmt::Write;
+
+/// Represents an error that occured while parsing a domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The parsed domain does not contain any value.
+    EmptyDomain,
+    /// A part of the union is empty (e.g. `1..3,,5..6`).
+    EmptyPart,
+    /// A literal is not a valid integer.
+    InvalidInteger(String),
+    /// A literal is not a valid boolean state (`0`, `1` or `?`).
+    InvalidBoolean(String),
+    /// The lower bound of a range is greater than its upper bound.
+    InvalidRange(i64, i64),
+    /// The parts of the union hold more than `MAX_DOMAIN_SIZE` values.
+    DomainTooLarge,
+    /// A set is opened but never closed, or closed but never opened.
+    UnbalancedBraces,
+    /// A part is neither a range `a..b` nor a set `{a,b,c}`.
+    UnexpectedToken(String),
+    /// The index of an assignment line is not the expected one.
+    UnexpectedIndex(usize),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::EmptyDomain => write!(f, "empty domain"),
+            ParseError::EmptyPart => write!(f, "empty part in the union"),
+            ParseError::InvalidInteger(s) => write!(f, "invalid integer `{}`", s),
+            ParseError::InvalidBoolean(s) => write!(f, "invalid boolean state `{}`", s),
+            ParseError::InvalidRange(min, max) => write!(f, "invalid range {}..{}", min, max),
+            ParseError::DomainTooLarge => {
+                write!(f, "domain larger than {} values", MAX_DOMAIN_SIZE)
+            }
+            ParseError::UnbalancedBraces => write!(f, "unbalanced braces"),
+            ParseError::UnexpectedToken(s) => write!(f, "unexpected token `{}`", s),
+            ParseError::UnexpectedIndex(index) => write!(f, "unexpected index {}", index),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Maximal number of values read by `parse_int_domain`, duplicates included. The values of
+/// the domain are stored one by one, so a larger domain (e.g. `0..9223372036854775807`, or a
+/// union of many large ranges) would exhaust the memory.
+pub const MAX_DOMAIN_SIZE: usize = 1 << 20;
+
+/// Parses a FlatZinc-like integer domain.
+///
+/// The accepted syntaxes are ranges `a..b`, sets `{a,b,c}` and unions of those separated
+/// either by `∪` or by a comma (e.g. `1..3 ∪ 7..9` or `1..3, 7..9`).
+/// Returns an error of type `ParseError::DomainTooLarge` if the parts hold more than
+/// `MAX_DOMAIN_SIZE` values in total.
+///
+/// # Parameters
+/// * `s` - The text to parse.
+pub fn parse_int_domain(s: &str) -> Result<IntVarValues<i64>, ParseError> {
+    let mut values = vec![];
+    for part in split_union(s)? {
+        parse_part(part.trim(), &mut values)?;
+    }
+    IntVarValues::new_from_values(values).ok_or(ParseError::EmptyDomain)
+}
+
+fn split_union(s: &str) -> Result<Vec<&str>, ParseError> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    for (index, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.checked_sub(1).ok_or(ParseError::UnbalancedBraces)?,
+            ',' | '∪' if depth == 0 => {
+                parts.push(&s[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(ParseError::UnbalancedBraces);
+    }
+    parts.push(&s[start..]);
+    Ok(parts)
+}
+
+fn parse_part(part: &str, values: &mut Vec<i64>) -> Result<(), ParseError> {
+    if part.is_empty() {
+        Err(ParseError::EmptyPart)
+    } else if let Some(set) = part.strip_prefix('{') {
+        let set = set.strip_suffix('}').ok_or(ParseError::UnbalancedBraces)?;
+        if set.trim().is_empty() {
+            return Ok(());
+        }
+        for value in set.split(',') {
+            let value = parse_integer(value)?;
+            if values.len() == MAX_DOMAIN_SIZE {
+                return Err(ParseError::DomainTooLarge);
+            }
+            values.push(value);
+        }
+        Ok(())
+    } else if let Some((min, max)) = part.split_once("..") {
+        let (min, max) = (parse_integer(min)?, parse_integer(max)?);
+        if min > max {
+            return Err(ParseError::InvalidRange(min, max));
+        }
+        // The range holds `max - min + 1` values, which have to fit in the remaining room.
+        if max.abs_diff(min) >= (MAX_DOMAIN_SIZE - values.len()) as u64 {
+            return Err(ParseError::DomainTooLarge);
+        }
+        values.extend(min..=max);
+        Ok(())
+    } else {
+        Err(ParseError::UnexpectedToken(part.to_string()))
+    }
+}
+
+fn parse_integer(s: &str) -> Result<i64, ParseError> {
+    let s = s.trim();
+    s.parse()
+        .map_err(|_| ParseError::InvalidInteger(s.to_string()))
+}
+
+/// Dumps an array of boolean variables as a partial assignment.
+/// Each variable is written on its own line as `v <index> <0|1|?>`, where `?` stands for
+/// a variable that is not affected yet.
+///
+/// # Parameters
+/// * `arr` - The array to dump.
+pub fn dump_bool_assignment(arr: &impl ArrayOfVariables<bool, BoolVar>) -> String {
+    let mut dump = String::new();
+    for (index, var) in arr.iter().enumerate() {
+        let state = match var.value() {
+            Some(true) => '1',
+            Some(false) => '0',
+            None => '?',
+        };
+        writeln!(dump, "v {} {}", index, state).unwrap();
+    }
+    dump
+}
+
+/// Loads an array of boolean variables from a partial assignment written by
+/// `dump_bool_assignment`. Lines have to be ordered by index and empty lines are ignored.
+///
+/// # Parameters
+/// * `s` - The text to parse.
+pub fn load_bool_assignment(s: &str) -> Result<ArrayOfVars<bool, BoolVar>, ParseError> {
+    let mut variables = vec![];
+    for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let tokens: Vec<_> = line.split_whitespace().collect();
+        let (index, state) = match tokens.as_slice() {
+            ["v", index, state] => (*index, *state),
+            _ => return Err(ParseError::UnexpectedToken(line.to_string())),
+        };
+        let index: usize = index
+            .parse()
+            .map_err(|_| ParseError::InvalidInteger(index.to_string()))?;
+        if index != variables.len() {
+            return Err(ParseError::UnexpectedIndex(index));
+        }
+        let mut var = BoolVar::new().unwrap();
+        match state {
+            "1" => {
+                let _ = var.set_value(true);
+            }
+            "0" => {
+                let _ = var.set_value(false);
+            }
+            "?" => {}
+            _ => return Err(ParseError::InvalidBoolean(state.to_string())),
+        }
+        variables.push(var);
+    }
+    Ok(ArrayOfVars::new_from_iter(variables).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        dump_bool_assignment, load_bool_assignment, parse_int_domain, ParseError, MAX_DOMAIN_SIZE,
+    };
+    use crate::bool_var::BoolVar;
+    use crate::domains::{AssignableDomain, FiniteDomain};
+    use crate::{ArrayOfVariables, ArrayOfVars};
+
+    #[test]
+    fn test_parse_range() {
+        let var = parse_int_domain("1..5").unwrap();
+        assert_eq!(var.as_slice(), &[1, 2, 3, 4, 5]);
+        let var = parse_int_domain(" -2 .. 1 ").unwrap();
+        assert_eq!(var.as_slice(), &[-2, -1, 0, 1]);
+    }
+
+    #[test]
+    fn test_parse_set() {
+        let var = parse_int_domain("{5,1,3}").unwrap();
+        assert_eq!(var.as_slice(), &[1, 3, 5]);
+        let var = parse_int_domain("{ 7 }").unwrap();
+        assert_eq!(var.as_slice(), &[7]);
+    }
+
+    #[test]
+    fn test_parse_union() {
+        let var = parse_int_domain("1..3 ∪ 7..9").unwrap();
+        assert_eq!(var.as_slice(), &[1, 2, 3, 7, 8, 9]);
+        let var = parse_int_domain("1..3, 7..9").unwrap();
+        assert_eq!(var.as_slice(), &[1, 2, 3, 7, 8, 9]);
+        let var = parse_int_domain("{10,12} ∪ 1..2, 2..3").unwrap();
+        assert_eq!(var.as_slice(), &[1, 2, 3, 10, 12]);
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert_eq!(parse_int_domain(""), Err(ParseError::EmptyPart));
+        assert_eq!(parse_int_domain("{}"), Err(ParseError::EmptyDomain));
+        assert_eq!(
+            parse_int_domain("5..1"),
+            Err(ParseError::InvalidRange(5, 1))
+        );
+        assert_eq!(parse_int_domain("{1,2"), Err(ParseError::UnbalancedBraces));
+        assert_eq!(parse_int_domain("1..2}"), Err(ParseError::UnbalancedBraces));
+        assert_eq!(parse_int_domain("1..3,,5..6"), Err(ParseError::EmptyPart));
+        assert_eq!(
+            parse_int_domain("1..x"),
+            Err(ParseError::InvalidInteger("x".to_string()))
+        );
+        assert_eq!(
+            parse_int_domain("{1,,2}"),
+            Err(ParseError::InvalidInteger("".to_string()))
+        );
+        assert_eq!(
+            parse_int_domain("42"),
+            Err(ParseError::UnexpectedToken("42".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_domain_too_large() {
+        let max = MAX_DOMAIN_SIZE as i64;
+        assert_eq!(
+            parse_int_domain(&format!("1..{}", max)).unwrap().size(),
+            MAX_DOMAIN_SIZE
+        );
+        assert_eq!(
+            parse_int_domain(&format!("0..{}", max)),
+            Err(ParseError::DomainTooLarge)
+        );
+        assert_eq!(
+            parse_int_domain("0..9223372036854775807"),
+            Err(ParseError::DomainTooLarge)
+        );
+        assert_eq!(
+            parse_int_domain("-9223372036854775808..9223372036854775807"),
+            Err(ParseError::DomainTooLarge)
+        );
+        let half = max / 2;
+        assert_eq!(
+            parse_int_domain(&format!("1..{}, 1..{}", half, half))
+                .unwrap()
+                .size(),
+            MAX_DOMAIN_SIZE / 2
+        );
+        assert_eq!(
+            parse_int_domain(&format!("1..{}, 1..{}, {{7}}", half, half)),
+            Err(ParseError::DomainTooLarge)
+        );
+        let many = vec![format!("0..{}", half - 1); 1000].join(" ∪ ");
+        assert_eq!(parse_int_domain(&many), Err(ParseError::DomainTooLarge));
+        assert_eq!(
+            ParseError::DomainTooLarge.to_string(),
+            format!("domain larger than {} values", MAX_DOMAIN_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        assert_eq!(ParseError::EmptyDomain.to_string(), "empty domain");
+        assert_eq!(
+            ParseError::InvalidInteger("x".to_string()).to_string(),
+            "invalid integer `x`"
+        );
+        assert_eq!(
+            ParseError::InvalidRange(5, 1).to_string(),
+            "invalid range 5..1"
+        );
+        assert_eq!(
+            ParseError::UnexpectedIndex(3).to_string(),
+            "unexpected index 3"
+        );
+        let parse = || -> Result<(), Box<dyn std::error::Error>> {
+            parse_int_domain("{1,2")?;
+            Ok(())
+        };
+        assert_eq!(parse().unwrap_err().to_string(), "unbalanced braces");
+    }
+
+    #[test]
+    fn test_bool_assignment_round_trip() {
+        let mut vars = ArrayOfVars::new(4, BoolVar::new().unwrap()).unwrap();
+        vars.get_mut(0).unwrap().set_value(true).unwrap();
+        vars.get_mut(2).unwrap().set_value(false).unwrap();
+        let dump = dump_bool_assignment(&vars);
+        assert_eq!(dump, "v 0 1\nv 1 ?\nv 2 0\nv 3 ?\n");
+
+        let loaded = load_bool_assignment(&dump).unwrap();
+        assert_eq!(loaded.len(), vars.len());
+        assert!(loaded.iter().zip(vars.iter()).all(|(lhs, rhs)| lhs == rhs));
+        assert_eq!(dump_bool_assignment(&loaded), dump);
+    }
+
+    #[test]
+    fn test_load_bool_assignment_malformed() {
+        assert_eq!(
+            load_bool_assignment("v 0 2").err(),
+            Some(ParseError::InvalidBoolean("2".to_string()))
+        );
+        assert_eq!(
+            load_bool_assignment("v 1 0").err(),
+            Some(ParseError::UnexpectedIndex(1))
+        );
+        assert_eq!(
+            load_bool_assignment("x 0 0").err(),
+            Some(ParseError::UnexpectedToken("x 0 0".to_string()))
+        );
+    }
+}
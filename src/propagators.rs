@@ -0,0 +1,708 @@
+use crate::array::value_histogram;
+use crate::bool_var::BoolVar;
+use crate::domains::{AssignableDomain, EqualDomain, OrderedDomain, PrunableDomain};
+use crate::int_var::{IntVarValues, IntVariableState};
+use crate::{ArrayOfVariables, Variable, VariableError};
+
+/// Bounds-consistency kernel of `sum(terms) = s` where `s` lies in `[total_min;total_max]`.
+/// The upper bound of each term is tightened to `total_max` minus the minimal values of the
+/// other terms and its lower bound to `total_min` minus their maximal values.
+/// Returns an error of type `VariableError::BoundWipeout` if a term has no value left,
+/// otherwise returns the merged `IntVariableState` of the terms.
+///
+/// # Parameters
+/// * `terms` - The terms of the sum.
+/// * `total_min` - The minimal value of the sum.
+/// * `total_max` - The maximal value of the sum.
+pub fn propagate_sum_term<T>(
+    terms: &mut impl ArrayOfVariables<T, IntVarValues<T>>,
+    total_min: T,
+    total_max: T,
+) -> Result<IntVariableState, VariableError>
+where
+    T: Copy + Ord + Eq + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + Default,
+{
+    let mut sum_min = T::default();
+    let mut sum_max = T::default();
+    for term in terms.iter() {
+        sum_min = sum_min + *term.unchecked_min();
+        sum_max = sum_max + *term.unchecked_max();
+    }
+    let mut state = IntVariableState::NoChange;
+    for term in terms.iter_mut() {
+        let (min, max) = (*term.unchecked_min(), *term.unchecked_max());
+        state = state | term.weak_upperbound(&(total_max - (sum_min - min)))?;
+        state = state | term.weak_lowerbound(&(total_min - (sum_max - max)))?;
+        sum_min = sum_min - min + *term.unchecked_min();
+        sum_max = sum_max - max + *term.unchecked_max();
+    }
+    Ok(state)
+}
+
+/// Posts `a[i] = b[i]` for each position `i` of two arrays of the same length.
+/// Returns an error of type `VariableError::InvalidArgument` if the lengths differ, an error
+/// of type `VariableError::DomainWipeout` if a pair of variables has no common value,
+/// otherwise returns the merged `IntVariableState` of all the variables.
+///
+/// # Parameters
+/// * `a` - The first array.
+/// * `b` - The second array.
+pub fn equal_arrays<T>(
+    a: &mut impl ArrayOfVariables<T, IntVarValues<T>>,
+    b: &mut impl ArrayOfVariables<T, IntVarValues<T>>,
+) -> Result<IntVariableState, VariableError>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    if a.len() != b.len() {
+        return Err(VariableError::InvalidArgument);
+    }
+    let mut state = IntVariableState::NoChange;
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        let (x_state, y_state) = x.equal(y)?;
+        state = state | x_state | y_state;
+    }
+    Ok(state)
+}
+
+/// Unit propagation of `alldifferent(arr)`, iterated to a fixpoint. The value of each
+/// affected variable is removed from the other variables. When the variables have to take
+/// all the values of the union of their domains (as many values as variables), a value that is
+/// supported by a single variable is assigned to it.
+/// Returns an error of type `VariableError::DomainWipeout` if a variable has no value left or
+/// if there are less values than variables, otherwise returns the merged `IntVariableState`
+/// of the variables.
+///
+/// # Parameters
+/// * `arr` - The variables that take distinct values.
+pub fn propagate_unique_support<T>(
+    arr: &mut impl ArrayOfVariables<T, IntVarValues<T>>,
+) -> Result<IntVariableState, VariableError>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    let mut state = IntVariableState::NoChange;
+    loop {
+        let mut change = IntVariableState::NoChange;
+        for i in 0..arr.len() {
+            if let Some(&value) = arr.get_unchecked(i).value() {
+                for j in (0..arr.len()).filter(|&j| j != i) {
+                    change = change | arr.get_unchecked_mut(j).remove_value(value)?;
+                }
+            }
+        }
+        let histogram = value_histogram(arr);
+        if histogram.len() < arr.len() {
+            return Err(VariableError::DomainWipeout);
+        }
+        if histogram.len() == arr.len() {
+            let unique = histogram
+                .into_iter()
+                .filter(|&(_, count)| count == 1)
+                .map(|(value, _)| value);
+            for value in unique {
+                let var = arr
+                    .iter_mut()
+                    .find(|var| var.as_slice().binary_search(&value).is_ok())
+                    .unwrap();
+                change = change | var.set_value(value)?;
+            }
+        }
+        if change == IntVariableState::NoChange {
+            return Ok(state);
+        }
+        state = state | change;
+    }
+}
+
+/// Posts `x1 = x2 = ... = xn` by restricting each variable to the intersection of all the
+/// domains, computed by linear merges.
+/// Returns an error of type `VariableError::DomainWipeout` if the domains share no value, in
+/// which case every variable is wiped out, otherwise returns the merged `IntVariableState` of
+/// the variables.
+///
+/// # Parameters
+/// * `vars` - The variables that are equal.
+pub fn intersect_all<T>(vars: &mut [IntVarValues<T>]) -> Result<IntVariableState, VariableError>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    let common = match vars.split_first() {
+        Some((first, others)) => others
+            .iter()
+            .fold(first.clone(), |common, var| &common & var),
+        None => return Ok(IntVariableState::NoChange),
+    };
+    if common.is_wiped() {
+        for var in vars.iter_mut().filter(|var| !var.is_wiped()) {
+            let _ = var.retain_in_iter(std::iter::empty());
+        }
+        return Err(VariableError::DomainWipeout);
+    }
+    let mut state = IntVariableState::NoChange;
+    for var in vars.iter_mut() {
+        state = state | var.retain_in_iter(common.as_slice().iter().copied())?;
+    }
+    Ok(state)
+}
+
+/// Bounds propagation of `m = min(xs)`. The bounds of `m` are tightened to the minimal lower
+/// bound and the minimal upper bound of the variables, and each variable is tightened to be
+/// greater than or equal to the minimal value of `m`. When a single variable can take a value
+/// lower than or equal to the maximal value of `m`, that variable is the minimum and is
+/// tightened to the maximal value of `m`. The propagation is iterated to a fixpoint.
+/// Returns an error of type `VariableError::InvalidArgument` if `xs` is empty, an error of
+/// type `VariableError::BoundWipeout` if a variable has no value left, otherwise returns the
+/// merged `IntVariableState` of all the variables.
+///
+/// # Parameters
+/// * `m` - The minimum of the variables.
+/// * `xs` - The variables.
+pub fn propagate_minimum<T>(
+    m: &mut IntVarValues<T>,
+    xs: &mut impl ArrayOfVariables<T, IntVarValues<T>>,
+) -> Result<IntVariableState, VariableError>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    if xs.len() == 0 {
+        return Err(VariableError::InvalidArgument);
+    }
+    let mut state = IntVariableState::NoChange;
+    loop {
+        let lb = xs.iter().map(|x| *x.unchecked_min()).min().unwrap();
+        let ub = xs.iter().map(|x| *x.unchecked_max()).min().unwrap();
+        let mut change = m.weak_lowerbound(&lb)? | m.weak_upperbound(&ub)?;
+        let (min, max) = (*m.unchecked_min(), *m.unchecked_max());
+        for x in xs.iter_mut() {
+            change = change | x.weak_lowerbound(&min)?;
+        }
+        let mut supports = xs.iter_mut().filter(|x| *x.unchecked_min() <= max);
+        if let (Some(x), None) = (supports.next(), supports.next()) {
+            change = change | x.weak_upperbound(&max)?;
+        }
+        if change == IntVariableState::NoChange {
+            return Ok(state);
+        }
+        state = state | change;
+    }
+}
+
+/// Bounds propagation of `m = max(xs)`. The bounds of `m` are tightened to the maximal lower
+/// bound and the maximal upper bound of the variables, and each variable is tightened to be
+/// lower than or equal to the maximal value of `m`. When a single variable can take a value
+/// greater than or equal to the minimal value of `m`, that variable is the maximum and is
+/// tightened to the minimal value of `m`. The propagation is iterated to a fixpoint.
+/// Returns an error of type `VariableError::InvalidArgument` if `xs` is empty, an error of
+/// type `VariableError::BoundWipeout` if a variable has no value left (e.g. when no variable
+/// can reach the minimal value of `m`), otherwise returns the merged `IntVariableState` of all
+/// the variables.
+///
+/// # Parameters
+/// * `m` - The maximum of the variables.
+/// * `xs` - The variables.
+pub fn propagate_maximum<T>(
+    m: &mut IntVarValues<T>,
+    xs: &mut impl ArrayOfVariables<T, IntVarValues<T>>,
+) -> Result<IntVariableState, VariableError>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    if xs.len() == 0 {
+        return Err(VariableError::InvalidArgument);
+    }
+    let mut state = IntVariableState::NoChange;
+    loop {
+        let lb = xs.iter().map(|x| *x.unchecked_min()).max().unwrap();
+        let ub = xs.iter().map(|x| *x.unchecked_max()).max().unwrap();
+        let mut change = m.weak_lowerbound(&lb)? | m.weak_upperbound(&ub)?;
+        let (min, max) = (*m.unchecked_min(), *m.unchecked_max());
+        for x in xs.iter_mut() {
+            change = change | x.weak_upperbound(&max)?;
+        }
+        let mut supports = xs.iter_mut().filter(|x| *x.unchecked_max() >= min);
+        if let (Some(x), None) = (supports.next(), supports.next()) {
+            change = change | x.weak_lowerbound(&min)?;
+        }
+        if change == IntVariableState::NoChange {
+            return Ok(state);
+        }
+        state = state | change;
+    }
+}
+
+/// Propagation of `b = (b1 ∨ b2)`. If `b` is false, both inputs are false. If an input is
+/// true, `b` is true. If both inputs are false, `b` is false. If `b` is true and an input is
+/// false, the other input is true.
+/// Returns an error of type `VariableError::DomainWipeout` if the variables are inconsistent,
+/// otherwise returns the merged `IntVariableState` of the three variables.
+///
+/// # Parameters
+/// * `b` - The disjunction.
+/// * `b1` - The first input.
+/// * `b2` - The second input.
+pub fn propagate_or(
+    b: &mut BoolVar,
+    b1: &mut BoolVar,
+    b2: &mut BoolVar,
+) -> Result<IntVariableState, VariableError> {
+    propagate_absorbing(b, b1, b2, true)
+}
+
+/// Propagation of `b = (b1 ∧ b2)`. If `b` is true, both inputs are true. If an input is
+/// false, `b` is false. If both inputs are true, `b` is true. If `b` is false and an input is
+/// true, the other input is false.
+/// Returns an error of type `VariableError::DomainWipeout` if the variables are inconsistent,
+/// otherwise returns the merged `IntVariableState` of the three variables.
+///
+/// # Parameters
+/// * `b` - The conjunction.
+/// * `b1` - The first input.
+/// * `b2` - The second input.
+pub fn propagate_and(
+    b: &mut BoolVar,
+    b1: &mut BoolVar,
+    b2: &mut BoolVar,
+) -> Result<IntVariableState, VariableError> {
+    propagate_absorbing(b, b1, b2, false)
+}
+
+/// Propagation of `b2 = ¬b1`. The value of a fixed variable is removed from the other one.
+/// Returns an error of type `VariableError::DomainWipeout` if both variables are fixed to the
+/// same value, otherwise returns the merged `IntVariableState` of the two variables.
+///
+/// # Parameters
+/// * `b1` - The first variable.
+/// * `b2` - The negation of the first variable.
+pub fn propagate_not(
+    b1: &mut BoolVar,
+    b2: &mut BoolVar,
+) -> Result<IntVariableState, VariableError> {
+    match (b1.value(), b2.value()) {
+        (Some(&v1), _) => b2.set_value(!v1),
+        (None, Some(&v2)) => b1.set_value(!v2),
+        (None, None) => Ok(IntVariableState::NoChange),
+    }
+}
+
+/// Propagation of `b = (x == c)`. If `b` is true, `x` is assigned to `c`. If `b` is false,
+/// `c` is removed from `x`. If `c` is not in the domain of `x`, `b` is false. If `x` is
+/// assigned to `c`, `b` is true.
+/// Returns an error of type `VariableError::DomainWipeout` if the variables are inconsistent,
+/// otherwise returns the merged `IntVariableState` of the two variables.
+///
+/// # Parameters
+/// * `b` - The truth value of the equality.
+/// * `x` - The variable.
+/// * `c` - The constant compared to `x`.
+pub fn propagate_reified_eq<T>(
+    b: &mut BoolVar,
+    x: &mut IntVarValues<T>,
+    c: T,
+) -> Result<IntVariableState, VariableError>
+where
+    T: Copy + Clone + Eq + PartialEq + Ord + PartialOrd,
+{
+    match b.value() {
+        Some(true) => x.set_value(c),
+        Some(false) => x.remove_value(c),
+        None if x.as_slice().binary_search(&c).is_err() => b.set_value(false),
+        None if x.value() == Some(&c) => b.set_value(true),
+        None => Ok(IntVariableState::NoChange),
+    }
+}
+
+// Propagation of `b = b1 op b2` where `absorbing` is the absorbing element of `op` (`true` for
+// the disjunction, `false` for the conjunction).
+fn propagate_absorbing(
+    b: &mut BoolVar,
+    b1: &mut BoolVar,
+    b2: &mut BoolVar,
+    absorbing: bool,
+) -> Result<IntVariableState, VariableError> {
+    let mut state = IntVariableState::NoChange;
+    loop {
+        let change = match (b.value(), b1.value(), b2.value()) {
+            (_, Some(&v1), Some(&v2)) if v1 == absorbing || v2 == absorbing => {
+                b.set_value(absorbing)?
+            }
+            (_, Some(_), Some(_)) => b.set_value(!absorbing)?,
+            (Some(&v), _, _) if v != absorbing => {
+                b1.set_value(!absorbing)? | b2.set_value(!absorbing)?
+            }
+            (_, Some(&v1), None) | (_, None, Some(&v1)) if v1 == absorbing => {
+                b.set_value(absorbing)?
+            }
+            (Some(_), Some(_), None) => b2.set_value(absorbing)?,
+            (Some(_), None, Some(_)) => b1.set_value(absorbing)?,
+            _ => IntVariableState::NoChange,
+        };
+        if change == IntVariableState::NoChange {
+            return Ok(state);
+        }
+        state = state | change;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::FromValuesDomain;
+    use crate::ArrayOfVars;
+
+    #[test]
+    fn test_propagate_sum_term() {
+        let mut terms = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(0..=10).unwrap(),
+            IntVarValues::new_from_values(vec![4, 5]).unwrap(),
+            IntVarValues::new_from_values(0..=2).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            propagate_sum_term(&mut terms, 12, 13),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(terms.get(0).unwrap().as_slice(), &[5, 6, 7, 8, 9]);
+        assert_eq!(terms.get(1).unwrap().as_slice(), &[4, 5]);
+        assert_eq!(terms.get(2).unwrap().as_slice(), &[0, 1, 2]);
+        assert_eq!(
+            propagate_sum_term(&mut terms, 12, 13),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(
+            propagate_sum_term(&mut terms, 30, 40),
+            Err(VariableError::BoundWipeout { lower: true })
+        );
+    }
+
+    #[test]
+    fn test_equal_arrays() {
+        let mut a = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(1..=5).unwrap(),
+        ])
+        .unwrap();
+        let mut b = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![2, 4, 9]).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            equal_arrays(&mut a, &mut b),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(a.get(1).unwrap().as_slice(), &[2, 4]);
+        assert_eq!(b.get(1).unwrap().as_slice(), &[2, 4]);
+        assert_eq!(equal_arrays(&mut a, &mut b), Ok(IntVariableState::NoChange));
+
+        let mut c = ArrayOfVars::new(1, IntVarValues::new_from_values(1..=5).unwrap()).unwrap();
+        assert_eq!(
+            equal_arrays(&mut a, &mut c),
+            Err(VariableError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_propagate_unique_support() {
+        let mut vars = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(vec![1]).unwrap(),
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![2, 3]).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            propagate_unique_support(&mut vars),
+            Ok(IntVariableState::BoundsChange)
+        );
+        let values: Vec<_> = vars.iter().map(|var| *var.value().unwrap()).collect();
+        assert_eq!(values, vec![1, 2, 3]);
+
+        let mut vars = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(1..=4).unwrap(),
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(1..=3).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            propagate_unique_support(&mut vars),
+            Ok(IntVariableState::MinBoundChange)
+        );
+        assert_eq!(vars.get(0).unwrap().value(), Some(&4));
+        assert_eq!(vars.get(3).unwrap().value(), Some(&3));
+        assert_eq!(vars.get(1).unwrap().as_slice(), &[1, 2]);
+
+        let mut vars = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![2, 3]).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            propagate_unique_support(&mut vars),
+            Ok(IntVariableState::NoChange)
+        );
+
+        let mut vars =
+            ArrayOfVars::new(3, IntVarValues::new_from_values(vec![1, 2]).unwrap()).unwrap();
+        assert_eq!(
+            propagate_unique_support(&mut vars),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_intersect_all() {
+        let mut vars = vec![
+            IntVarValues::new_from_values(1..=6).unwrap(),
+            IntVarValues::new_from_values(vec![2, 3, 4, 6]).unwrap(),
+            IntVarValues::new_from_values(vec![3, 4, 6, 9]).unwrap(),
+        ];
+        assert_eq!(intersect_all(&mut vars), Ok(IntVariableState::BoundsChange));
+        assert!(vars.iter().all(|var| var.as_slice() == [3, 4, 6]));
+        assert_eq!(intersect_all(&mut vars), Ok(IntVariableState::NoChange));
+
+        let mut vars = vec![
+            IntVarValues::new_from_values(vec![1, 2]).unwrap(),
+            IntVarValues::new_from_values(vec![2, 3]).unwrap(),
+            IntVarValues::new_from_values(vec![1, 3]).unwrap(),
+        ];
+        assert_eq!(intersect_all(&mut vars), Err(VariableError::DomainWipeout));
+        assert!(vars.iter().all(|var| var.is_wiped()));
+    }
+
+    #[test]
+    fn test_propagate_minimum() {
+        let mut m = IntVarValues::new_from_values(0..=5).unwrap();
+        let mut xs = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(1..=9).unwrap(),
+            IntVarValues::new_from_values(7..=9).unwrap(),
+            IntVarValues::new_from_values(vec![8, 10]).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            propagate_minimum(&mut m, &mut xs),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(m.as_slice(), &[1, 2, 3, 4, 5]);
+        assert_eq!(xs.get(0).unwrap().as_slice(), &[1, 2, 3, 4, 5]);
+        assert_eq!(
+            propagate_minimum(&mut m, &mut xs),
+            Ok(IntVariableState::NoChange)
+        );
+        m.weak_lowerbound(&3).unwrap();
+        assert_eq!(
+            propagate_minimum(&mut m, &mut xs),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(xs.get(0).unwrap().as_slice(), &[3, 4, 5]);
+        assert_eq!(xs.get(2).unwrap().as_slice(), &[8, 10]);
+
+        let mut m = IntVarValues::new_from_values(0..=2).unwrap();
+        assert_eq!(
+            propagate_minimum(&mut m, &mut xs),
+            Err(VariableError::BoundWipeout { lower: true })
+        );
+    }
+
+    #[test]
+    fn test_propagate_maximum() {
+        let mut m = IntVarValues::new_from_values(5..=10).unwrap();
+        let mut xs = ArrayOfVars::new_from_iter(vec![
+            IntVarValues::new_from_values(1..=9).unwrap(),
+            IntVarValues::new_from_values(1..=3).unwrap(),
+            IntVarValues::new_from_values(vec![0, 2]).unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            propagate_maximum(&mut m, &mut xs),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(m.as_slice(), &[5, 6, 7, 8, 9]);
+        assert_eq!(xs.get(0).unwrap().as_slice(), &[5, 6, 7, 8, 9]);
+        assert_eq!(
+            propagate_maximum(&mut m, &mut xs),
+            Ok(IntVariableState::NoChange)
+        );
+        m.weak_upperbound(&7).unwrap();
+        assert_eq!(
+            propagate_maximum(&mut m, &mut xs),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(xs.get(0).unwrap().as_slice(), &[5, 6, 7]);
+        assert_eq!(xs.get(2).unwrap().as_slice(), &[0, 2]);
+
+        let mut m = IntVarValues::new_from_values(8..=10).unwrap();
+        assert_eq!(
+            propagate_maximum(&mut m, &mut xs),
+            Err(VariableError::BoundWipeout { lower: false })
+        );
+    }
+
+    fn fixed(value: Option<bool>) -> BoolVar {
+        let mut var = BoolVar::new().unwrap();
+        if let Some(value) = value {
+            var.set_value(value).unwrap();
+        }
+        var
+    }
+
+    #[test]
+    fn test_propagate_or() {
+        let propagate = |b, b1, b2| {
+            let (mut b, mut b1, mut b2) = (fixed(b), fixed(b1), fixed(b2));
+            propagate_or(&mut b, &mut b1, &mut b2)
+                .map(|_| (b.value().copied(), b1.value().copied(), b2.value().copied()))
+        };
+        assert_eq!(
+            propagate(Some(false), None, None),
+            Ok((Some(false), Some(false), Some(false)))
+        );
+        assert_eq!(
+            propagate(None, Some(true), None),
+            Ok((Some(true), Some(true), None))
+        );
+        assert_eq!(
+            propagate(None, None, Some(true)),
+            Ok((Some(true), None, Some(true)))
+        );
+        assert_eq!(
+            propagate(None, Some(false), Some(false)),
+            Ok((Some(false), Some(false), Some(false)))
+        );
+        assert_eq!(
+            propagate(Some(true), Some(false), None),
+            Ok((Some(true), Some(false), Some(true)))
+        );
+        assert_eq!(
+            propagate(Some(true), None, Some(false)),
+            Ok((Some(true), Some(true), Some(false)))
+        );
+        assert_eq!(
+            propagate(Some(true), None, None),
+            Ok((Some(true), None, None))
+        );
+        assert_eq!(
+            propagate(None, Some(false), None),
+            Ok((None, Some(false), None))
+        );
+        assert_eq!(
+            propagate(Some(false), Some(true), None),
+            Err(VariableError::DomainWipeout)
+        );
+        assert_eq!(
+            propagate(Some(true), Some(false), Some(false)),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_propagate_and() {
+        let propagate = |b, b1, b2| {
+            let (mut b, mut b1, mut b2) = (fixed(b), fixed(b1), fixed(b2));
+            propagate_and(&mut b, &mut b1, &mut b2)
+                .map(|_| (b.value().copied(), b1.value().copied(), b2.value().copied()))
+        };
+        assert_eq!(
+            propagate(Some(true), None, None),
+            Ok((Some(true), Some(true), Some(true)))
+        );
+        assert_eq!(
+            propagate(None, Some(false), None),
+            Ok((Some(false), Some(false), None))
+        );
+        assert_eq!(
+            propagate(None, None, Some(false)),
+            Ok((Some(false), None, Some(false)))
+        );
+        assert_eq!(
+            propagate(None, Some(true), Some(true)),
+            Ok((Some(true), Some(true), Some(true)))
+        );
+        assert_eq!(
+            propagate(Some(false), Some(true), None),
+            Ok((Some(false), Some(true), Some(false)))
+        );
+        assert_eq!(
+            propagate(Some(false), None, Some(true)),
+            Ok((Some(false), Some(false), Some(true)))
+        );
+        assert_eq!(
+            propagate(Some(false), None, None),
+            Ok((Some(false), None, None))
+        );
+        assert_eq!(
+            propagate(Some(true), Some(false), None),
+            Err(VariableError::DomainWipeout)
+        );
+        assert_eq!(
+            propagate(Some(false), Some(true), Some(true)),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_propagate_not() {
+        let (mut b1, mut b2) = (fixed(Some(true)), fixed(None));
+        assert_eq!(
+            propagate_not(&mut b1, &mut b2),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(b2.value(), Some(&false));
+        assert_eq!(
+            propagate_not(&mut b1, &mut b2),
+            Ok(IntVariableState::NoChange)
+        );
+
+        let (mut b1, mut b2) = (fixed(None), fixed(Some(true)));
+        propagate_not(&mut b1, &mut b2).unwrap();
+        assert_eq!(b1.value(), Some(&false));
+
+        let (mut b1, mut b2) = (fixed(None), fixed(None));
+        assert_eq!(
+            propagate_not(&mut b1, &mut b2),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(
+            propagate_not(&mut fixed(Some(false)), &mut fixed(Some(false))),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+
+    #[test]
+    fn test_propagate_reified_eq() {
+        let mut x = IntVarValues::new_from_values(1..=5).unwrap();
+        assert_eq!(
+            propagate_reified_eq(&mut fixed(Some(false)), &mut x, 3),
+            Ok(IntVariableState::ValuesChange)
+        );
+        assert_eq!(x.as_slice(), &[1, 2, 4, 5]);
+
+        let mut b = fixed(None);
+        assert_eq!(
+            propagate_reified_eq(&mut b, &mut x, 3),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(b.value(), Some(&false));
+
+        let mut b = fixed(None);
+        assert_eq!(
+            propagate_reified_eq(&mut b, &mut x, 4),
+            Ok(IntVariableState::NoChange)
+        );
+        assert_eq!(b.value(), None);
+
+        assert_eq!(
+            propagate_reified_eq(&mut fixed(Some(true)), &mut x, 4),
+            Ok(IntVariableState::BoundsChange)
+        );
+        assert_eq!(x.value(), Some(&4));
+
+        let mut b = fixed(None);
+        propagate_reified_eq(&mut b, &mut x, 4).unwrap();
+        assert_eq!(b.value(), Some(&true));
+        assert_eq!(
+            propagate_reified_eq(&mut fixed(Some(false)), &mut x, 4),
+            Err(VariableError::DomainWipeout)
+        );
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(feature = "observer")]
+use crate::int_var::IntVariableState;
+#[cfg(feature = "observer")]
+use crate::{VariableError, VariableObserver};
+use crusp_core::VariableId;
+use std::collections::{HashSet, VecDeque};
+
+/// Worklist of the variables whose domain changed and whose propagators have to be woken up.
+/// A variable is queued at most once, in the order of its first push.
+#[derive(Debug, Clone, Default)]
+pub struct DirtyQueue {
+    queue: VecDeque<VariableId>,
+    queued: HashSet<VariableId>,
+}
+
+impl DirtyQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        DirtyQueue::default()
+    }
+
+    /// Enqueues the variable unless it is already queued.
+    /// Returns `true` iff the variable has been enqueued.
+    ///
+    /// # Parameters
+    /// * `id` - The id of the variable.
+    pub fn push(&mut self, id: VariableId) -> bool {
+        if self.queued.insert(id) {
+            self.queue.push_back(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Dequeues the oldest queued variable or returns `None` if the queue is empty.
+    pub fn pop(&mut self) -> Option<VariableId> {
+        let id = self.queue.pop_front()?;
+        self.queued.remove(&id);
+        Some(id)
+    }
+
+    /// Returns the number of queued variables.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns `true` iff no variable is queued.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+/// Observer that schedules the variables whose domain changed into a `DirtyQueue`.
+/// After the first error, the failing variable is recorded and the following events are
+/// ignored.
+#[cfg(feature = "observer")]
+#[derive(Debug)]
+pub struct QueueObserver<'a> {
+    queue: &'a mut DirtyQueue,
+    failed: Option<VariableId>,
+}
+
+#[cfg(feature = "observer")]
+impl<'a> QueueObserver<'a> {
+    /// Creates an observer that fills `queue`.
+    ///
+    /// # Parameters
+    /// * `queue` - The queue of the woken up variables.
+    pub fn new(queue: &'a mut DirtyQueue) -> Self {
+        QueueObserver {
+            queue,
+            failed: None,
+        }
+    }
+
+    /// Returns the id of the variable that failed first, if any.
+    pub fn failed(&self) -> Option<VariableId> {
+        self.failed
+    }
+}
+
+#[cfg(feature = "observer")]
+impl<'a> VariableObserver<IntVariableState> for QueueObserver<'a> {
+    fn push(
+        &mut self,
+        vid: VariableId,
+        event: Result<IntVariableState, VariableError>,
+    ) -> Result<IntVariableState, VariableError> {
+        match event {
+            Ok(state) => self.push_change(vid, state),
+            Err(error) => self.push_error(vid, error),
+        }
+    }
+
+    fn push_change(
+        &mut self,
+        vid: VariableId,
+        event: IntVariableState,
+    ) -> Result<IntVariableState, VariableError> {
+        if self.failed.is_none() && event != IntVariableState::NoChange {
+            self.queue.push(vid);
+        }
+        Ok(event)
+    }
+
+    fn push_error(
+        &mut self,
+        vid: VariableId,
+        event: VariableError,
+    ) -> Result<IntVariableState, VariableError> {
+        self.failed.get_or_insert(vid);
+        Err(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_dedup() {
+        let mut queue = DirtyQueue::new();
+        assert!(queue.push(VariableId::new(3)));
+        assert!(queue.push(VariableId::new(1)));
+        assert!(!queue.push(VariableId::new(3)));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), Some(VariableId::new(3)));
+        assert!(queue.push(VariableId::new(3)));
+        assert_eq!(queue.pop(), Some(VariableId::new(1)));
+        assert_eq!(queue.pop(), Some(VariableId::new(3)));
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+    }
+}
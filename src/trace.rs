@@ -0,0 +1,300 @@
+use crate::domains::{
+    AssignableDomain, EqualDomain, FiniteDomain, IterableDomain, OrderedDomain,
+    OrderedPrunableDomain, PrunableDomain,
+};
+use crate::{Variable, VariableError, VariableState};
+use crusp_core::VariableId;
+use std::cell::RefCell;
+
+type TraceCallback = Box<dyn Fn(&str, VariableId)>;
+
+thread_local! {
+    static CALLBACK: RefCell<Option<TraceCallback>> = RefCell::new(None);
+}
+
+/// Registers the callback invoked after each operation of a `Traced` variable of the current
+/// thread that changed its domain or failed. The callback receives the name of the operation
+/// and the id of the variable.
+///
+/// # Parameters
+/// * `callback` - The callback to register.
+pub fn set_trace_callback<F>(callback: F)
+where
+    F: Fn(&str, VariableId) + 'static,
+{
+    CALLBACK.with(|cell| *cell.borrow_mut() = Some(Box::new(callback)));
+}
+
+/// Removes the callback registered for the current thread.
+pub fn clear_trace_callback() {
+    CALLBACK.with(|cell| *cell.borrow_mut() = None);
+}
+
+// Reports `operation` to the callback unless it left the domain unchanged.
+fn trace<VState>(
+    operation: &str,
+    id: VariableId,
+    result: Result<VState, VariableError>,
+) -> Result<VState, VariableError>
+where
+    VState: VariableState,
+{
+    if result.as_ref().map_or(true, |state| !state.is_null()) {
+        report(operation, id);
+    }
+    result
+}
+
+// Same as `trace` for an operation on two variables: each variable whose domain changed is
+// reported, and both are reported if the operation failed.
+fn trace_pair<VState>(
+    operation: &str,
+    ids: (VariableId, VariableId),
+    result: Result<(VState, VState), VariableError>,
+) -> Result<(VState, VState), VariableError>
+where
+    VState: VariableState,
+{
+    let (first, second) = match result {
+        Ok((ref first, ref second)) => (!first.is_null(), !second.is_null()),
+        Err(_) => (true, true),
+    };
+    if first {
+        report(operation, ids.0);
+    }
+    if second {
+        report(operation, ids.1);
+    }
+    result
+}
+
+fn report(operation: &str, id: VariableId) {
+    CALLBACK.with(|cell| {
+        if let Some(callback) = cell.borrow().as_ref() {
+            callback(operation, id);
+        }
+    });
+}
+
+/// Wrapper around a variable that reports to the trace callback each operation that changed
+/// its domain or failed.
+/// Only the methods of the domain traits (`AssignableDomain`, `OrderedDomain`,
+/// `PrunableDomain`, `EqualDomain` and `OrderedPrunableDomain`) are traced. The inherent
+/// methods of the underlying variable (e.g. `IntVarValues::retain_in_iter`) are not, and
+/// neither are the functions of `propagators`, which take `IntVarValues` directly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Traced<Var> {
+    id: VariableId,
+    var: Var,
+}
+
+impl<Var> Traced<Var> {
+    /// Creates a new traced variable.
+    ///
+    /// # Parameters
+    /// * `id` - The id given to the callback.
+    /// * `var` - The traced variable.
+    pub fn new(id: VariableId, var: Var) -> Self {
+        Traced { id, var }
+    }
+
+    /// Returns the id of the variable.
+    pub fn id(&self) -> VariableId {
+        self.id
+    }
+
+    /// Returns a reference to the underlying variable.
+    pub fn inner(&self) -> &Var {
+        &self.var
+    }
+
+    /// Returns the underlying variable.
+    pub fn into_inner(self) -> Var {
+        self.var
+    }
+}
+
+impl<Type, Var> Variable<Type> for Traced<Var>
+where
+    Var: Variable<Type>,
+{
+    fn is_affected(&self) -> bool {
+        self.var.is_affected()
+    }
+
+    fn value(&self) -> Option<&Type> {
+        self.var.value()
+    }
+}
+
+impl<Type, Var> FiniteDomain<Type> for Traced<Var>
+where
+    Var: FiniteDomain<Type>,
+{
+    fn size(&self) -> usize {
+        self.var.size()
+    }
+}
+
+impl<Type, Var> IterableDomain<Type> for Traced<Var>
+where
+    Var: IterableDomain<Type>,
+{
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Type> + 'a> {
+        self.var.iter()
+    }
+}
+
+impl<Type, VState, Var> AssignableDomain<Type, VState> for Traced<Var>
+where
+    VState: VariableState,
+    Var: AssignableDomain<Type, VState>,
+{
+    fn set_value(&mut self, value: Type) -> Result<VState, VariableError> {
+        trace("set_value", self.id, self.var.set_value(value))
+    }
+}
+
+impl<Type, VState, Var> OrderedDomain<Type, VState> for Traced<Var>
+where
+    VState: VariableState,
+    Type: Ord + Eq,
+    Var: OrderedDomain<Type, VState>,
+{
+    fn min(&self) -> Option<&Type> {
+        self.var.min()
+    }
+    fn max(&self) -> Option<&Type> {
+        self.var.max()
+    }
+    fn strict_upperbound(&mut self, ub: &Type) -> Result<VState, VariableError> {
+        trace("strict_upperbound", self.id, self.var.strict_upperbound(ub))
+    }
+    fn weak_upperbound(&mut self, ub: &Type) -> Result<VState, VariableError> {
+        trace("weak_upperbound", self.id, self.var.weak_upperbound(ub))
+    }
+    fn strict_lowerbound(&mut self, lb: &Type) -> Result<VState, VariableError> {
+        trace("strict_lowerbound", self.id, self.var.strict_lowerbound(lb))
+    }
+    fn weak_lowerbound(&mut self, lb: &Type) -> Result<VState, VariableError> {
+        trace("weak_lowerbound", self.id, self.var.weak_lowerbound(lb))
+    }
+}
+
+impl<Type, VState, Var> PrunableDomain<Type, VState> for Traced<Var>
+where
+    Type: Eq,
+    VState: VariableState,
+    Var: PrunableDomain<Type, VState>,
+{
+    fn in_values<Values>(&mut self, values: Values) -> Result<VState, VariableError>
+    where
+        Values: IntoIterator<Item = Type>,
+    {
+        trace("in_values", self.id, self.var.in_values(values))
+    }
+
+    fn remove_value(&mut self, value: Type) -> Result<VState, VariableError> {
+        trace("remove_value", self.id, self.var.remove_value(value))
+    }
+
+    fn remove_if<Predicate>(&mut self, pred: Predicate) -> Result<VState, VariableError>
+    where
+        Predicate: FnMut(&Type) -> bool,
+    {
+        trace("remove_if", self.id, self.var.remove_if(pred))
+    }
+
+    fn retains_if<Predicate>(&mut self, pred: Predicate) -> Result<VState, VariableError>
+    where
+        Predicate: FnMut(&Type) -> bool,
+    {
+        trace("retains_if", self.id, self.var.retains_if(pred))
+    }
+}
+
+impl<Type, VState, Var> EqualDomain<Type, VState> for Traced<Var>
+where
+    Type: Eq,
+    VState: VariableState,
+    Var: EqualDomain<Type, VState>,
+{
+    fn equal(&mut self, value: &mut Traced<Var>) -> Result<(VState, VState), VariableError> {
+        let ids = (self.id, value.id);
+        trace_pair("equal", ids, self.var.equal(&mut value.var))
+    }
+
+    fn not_equal(&mut self, value: &mut Traced<Var>) -> Result<(VState, VState), VariableError> {
+        let ids = (self.id, value.id);
+        trace_pair("not_equal", ids, self.var.not_equal(&mut value.var))
+    }
+}
+
+impl<Type, VState, Var> OrderedPrunableDomain<Type, VState> for Traced<Var>
+where
+    Type: Eq + Ord,
+    VState: VariableState,
+    Var: OrderedPrunableDomain<Type, VState>,
+{
+    fn in_sorted_values<Values>(&mut self, values: Values) -> Result<VState, VariableError>
+    where
+        Values: IntoIterator<Item = Type>,
+    {
+        trace(
+            "in_sorted_values",
+            self.id,
+            self.var.in_sorted_values(values),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::FromValuesDomain;
+    use crate::int_var::IntVarValues;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_trace_callback() {
+        let log = Rc::new(RefCell::new(vec![]));
+        let sink = Rc::clone(&log);
+        set_trace_callback(move |operation, id| {
+            sink.borrow_mut().push((operation.to_string(), id));
+        });
+        let (x_id, y_id) = (VariableId::new(0), VariableId::new(1));
+        let mut x = Traced::new(x_id, IntVarValues::new_from_values(1..=10).unwrap());
+        let mut y = Traced::new(y_id, IntVarValues::new_from_values(1..=10).unwrap());
+        x.weak_upperbound(&8).unwrap();
+        x.weak_upperbound(&9).unwrap();
+        y.remove_value(3).unwrap();
+        x.retains_if(|v| v % 2 == 0).unwrap();
+        y.set_value(5).unwrap();
+        y.set_value(5).unwrap();
+        assert_eq!(y.remove_value(5), Err(VariableError::DomainWipeout));
+        let (z_id, w_id) = (VariableId::new(2), VariableId::new(3));
+        let mut z = Traced::new(z_id, IntVarValues::new_from_values(1..=5).unwrap());
+        let mut w = Traced::new(w_id, IntVarValues::new_from_values(vec![2, 4, 6]).unwrap());
+        z.equal(&mut w).unwrap();
+        z.equal(&mut w).unwrap();
+        w.in_sorted_values(vec![4, 9]).unwrap();
+        clear_trace_callback();
+        x.remove_value(4).unwrap();
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                ("weak_upperbound".to_string(), x_id),
+                ("remove_value".to_string(), y_id),
+                ("retains_if".to_string(), x_id),
+                ("set_value".to_string(), y_id),
+                ("remove_value".to_string(), y_id),
+                ("equal".to_string(), z_id),
+                ("equal".to_string(), w_id),
+                ("in_sorted_values".to_string(), w_id),
+            ]
+        );
+        assert_eq!(x.inner().as_slice(), &[2, 6, 8]);
+        assert_eq!(z.inner().as_slice(), &[2, 4]);
+        assert_eq!(w.inner().as_slice(), &[4]);
+    }
+}